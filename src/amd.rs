@@ -2,33 +2,81 @@
 //
 // This is pretty hacky!  Something better than this is likely needed and hopefully possible.
 
+use crate::amd_smi;
+use crate::amd_sysfs;
 use crate::command::{self, CmdError};
+use crate::drm_fdinfo;
 use crate::gpu;
 use crate::ps::UserTable;
 use crate::TIMEOUT_SECONDS;
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::path::Path;
 
 #[cfg(test)]
 use crate::util::map;
 
-pub struct AmdGPU {}
+pub struct AmdGPU {
+    // Set at probe time if librocm_smi64 could be opened and saw at least one card; `None` means
+    // we're running the `rocm-smi` text-scraping path below instead.  Kept around rather than
+    // re-probed on every call since `amdml_init`/`amdml_shutdown` bracket a whole session.
+    ndev: Option<cty::uint32_t>,
+    // The previous sample's per-pid `drm-engine-gfx` nanoseconds and when it was taken, used by
+    // the text-scraping path to turn `get_amd_utilization`'s one-shot fdinfo reading into a real
+    // `gpu_pct` via `drm_fdinfo::interval_gpu_pct` - the same previous/current state-threading
+    // convention as `interval_cpu_pct`/`cpu_ticks_snapshot` in procfs.rs, just held here across
+    // calls instead of passed in by a caller, since this struct (like `ndev` above) already
+    // persists for the life of the session.  Empty/`None` on the first sample, which yields no
+    // interval and falls back to the even split below.
+    prev_engine_ns: HashMap<usize, u64>,
+    prev_sample_at: Option<std::time::Instant>,
+}
 
 pub fn probe() -> Option<Box<dyn gpu::GPU>> {
+    if let Some(ndev) = amd_smi::open() {
+        return Some(Box::new(AmdGPU {
+            ndev: Some(ndev),
+            prev_engine_ns: HashMap::new(),
+            prev_sample_at: None,
+        }));
+    }
     if amd_present() {
-        Some(Box::new(AmdGPU {}))
+        Some(Box::new(AmdGPU {
+            ndev: None,
+            prev_engine_ns: HashMap::new(),
+            prev_sample_at: None,
+        }))
     } else {
         None
     }
 }
 
+impl Drop for AmdGPU {
+    fn drop(&mut self) {
+        if self.ndev.is_some() {
+            amd_smi::close();
+        }
+    }
+}
+
 impl gpu::GPU for AmdGPU {
     fn get_manufacturer(&mut self) -> String {
         "AMD".to_string()
     }
 
     fn get_card_configuration(&mut self) -> Result<Vec<gpu::Card>, String> {
+        if let Some(ndev) = self.ndev {
+            return Ok(amd_smi::get_card_configuration(ndev));
+        }
+        // rocm-smi can't report installed VRAM on some of our hardware (see the comment on
+        // `get_amd_configuration` below); sysfs can, so prefer it over the text scraper when it
+        // has anything to say, and only fall back to rocm-smi if sysfs found no AMD cards either
+        // (eg a kernel without amdgpu's extra sysfs attributes).
+        let sysfs_cards = amd_sysfs::get_card_configuration();
+        if !sysfs_cards.is_empty() {
+            return Ok(sysfs_cards);
+        }
         get_amd_configuration()
     }
 
@@ -36,11 +84,30 @@ impl gpu::GPU for AmdGPU {
         &mut self,
         user_by_pid: &UserTable,
     ) -> Result<Vec<gpu::Process>, String> {
-        get_amd_utilization(user_by_pid)
+        if self.ndev.is_some() {
+            return Ok(amd_smi::get_process_utilization(user_by_pid));
+        }
+        let now = std::time::Instant::now();
+        let elapsed_secs = self
+            .prev_sample_at
+            .map(|t| now.duration_since(t).as_secs_f64())
+            .unwrap_or(0.0);
+        let (processes, current_engine_ns) =
+            get_amd_utilization(user_by_pid, &self.prev_engine_ns, elapsed_secs)?;
+        self.prev_engine_ns = current_engine_ns;
+        self.prev_sample_at = Some(now);
+        Ok(processes)
     }
 
     fn get_card_utilization(&mut self) -> Result<Vec<gpu::CardState>, String> {
-        Ok(vec![])
+        if let Some(ndev) = self.ndev {
+            return Ok(amd_smi::get_card_utilization(ndev));
+        }
+        let sysfs_state = amd_sysfs::get_card_utilization();
+        if !sysfs_state.is_empty() {
+            return Ok(sysfs_state);
+        }
+        get_amd_card_utilization_from_text()
     }
 }
 
@@ -102,15 +169,44 @@ fn get_amd_configuration() -> Result<Vec<gpu::Card>, String> {
 // Err(e) really means the command started running but failed, for the reason given.  If the
 // command could not be found or no card is present, we return Ok(vec![]).
 
-fn get_amd_utilization(user_by_pid: &UserTable) -> Result<Vec<gpu::Process>, String> {
+fn get_amd_utilization(
+    user_by_pid: &UserTable,
+    prev_engine_ns: &HashMap<usize, u64>,
+    elapsed_secs: f64,
+) -> Result<(Vec<gpu::Process>, HashMap<usize, u64>), String> {
     // I've not been able to combine the two invocations of rocm-smi yet; we have to run the command
     // twice.  Not a happy situation.
 
-    Ok(extract_amd_information(
+    let per_pid_info = get_raw_per_pid_info()?;
+    // `--showpidgpus` only tells us *which* devices a pid touches, not its actual share of either;
+    // get the real numbers, where we can, from DRM fdinfo (see drm_fdinfo.rs) instead of dividing
+    // each device's total evenly across its users below.
+    let pid_fdinfo = per_pid_info
+        .iter()
+        .filter_map(|(pid, _)| Some((*pid, drm_fdinfo::read_process_totals(*pid, "amdgpu")?)))
+        .collect::<HashMap<usize, drm_fdinfo::FdInfoTotals>>();
+    // `drm-engine-gfx` (rolled into `FdInfoTotals::engine_ns`) is cumulative, so this one reading
+    // only becomes a real `gpu_pct` once differenced against the previous sample's reading for the
+    // same pid; see `interval_gpu_pct` in drm_fdinfo.rs.  Hand the raw current reading back to the
+    // caller so it can be threaded in as `prev_engine_ns` on the next call.
+    let current_engine_ns = pid_fdinfo
+        .iter()
+        .map(|(pid, totals)| (*pid, totals.engine_ns))
+        .collect::<HashMap<usize, u64>>();
+    let interval_gpu_pct = drm_fdinfo::interval_gpu_pct(prev_engine_ns, &current_engine_ns, elapsed_secs);
+    let device_mem_kib = amd_sysfs::get_card_configuration()
+        .iter()
+        .map(|c| c.mem_size_kib)
+        .collect::<Vec<usize>>();
+    let processes = extract_amd_information(
         &get_raw_per_device_info()?,
-        &get_raw_per_pid_info()?,
+        &per_pid_info,
         user_by_pid,
-    ))
+        &pid_fdinfo,
+        &device_mem_kib,
+        &interval_gpu_pct,
+    );
+    Ok((processes, current_engine_ns))
 }
 
 // Put it all together from the command output.
@@ -119,6 +215,9 @@ fn extract_amd_information(
     per_device_info: &[(f64, f64)],
     per_pid_info: &[(usize, Vec<usize>)],
     user_by_pid: &UserTable,
+    pid_fdinfo: &HashMap<usize, drm_fdinfo::FdInfoTotals>,
+    device_mem_kib: &[usize],
+    interval_gpu_pct: &HashMap<usize, f64>,
 ) -> Vec<gpu::Process> {
     let mut num_processes_per_device = vec![0; per_device_info.len()];
     per_pid_info.iter().for_each(|(_, devs)| {
@@ -126,8 +225,6 @@ fn extract_amd_information(
             .for_each(|dev| num_processes_per_device[*dev] += 1)
     });
     let mut processes = vec![];
-    // The utilization for one process on one device is the total utilization for the device
-    // divided by the number of processes using the device.
     per_pid_info.iter().for_each(|(pid, devs)| {
         devs.iter().for_each(|dev| {
             let (user, uid) = if let Some((user, uid)) = user_by_pid.get(pid) {
@@ -135,15 +232,40 @@ fn extract_amd_information(
             } else {
                 ("_zombie_".to_owned() + &pid.to_string(), gpu::ZOMBIE_UID)
             };
+            // Real per-process VRAM from fdinfo, where available, rather than the even-split
+            // heuristic; likewise, real per-process `gpu_pct` from differencing this and the
+            // previous sample's `drm-engine-gfx` nanoseconds (`interval_gpu_pct`, built by the
+            // caller from two `current_engine_ns` snapshots) where both readings exist.  The first
+            // sample of a session has no previous reading to difference against, so falls back to
+            // the even split below, same as when fdinfo itself is unavailable.
+            let even_split_gpu_pct = per_device_info[*dev].0 / num_processes_per_device[*dev] as f64;
+            let even_split_mem_pct = per_device_info[*dev].1 / num_processes_per_device[*dev] as f64;
+            let gpu_pct = interval_gpu_pct.get(pid).copied().unwrap_or(even_split_gpu_pct);
+            let (mem_size_kib, mem_pct, command) = match pid_fdinfo.get(pid) {
+                Some(totals) if totals.memory_bytes > 0 => {
+                    let mem_size_kib = (totals.memory_bytes / 1024) as usize;
+                    let mem_pct = match device_mem_kib.get(*dev) {
+                        Some(total_kib) if *total_kib > 0 => {
+                            100.0 * mem_size_kib as f64 / *total_kib as f64
+                        }
+                        _ => even_split_mem_pct,
+                    };
+                    let command = std::fs::read_to_string(format!("/proc/{pid}/comm"))
+                        .map(|s| s.trim().to_string())
+                        .unwrap_or_else(|_| "_noinfo_".to_string());
+                    (mem_size_kib, mem_pct, command)
+                }
+                _ => (0, even_split_mem_pct, "_noinfo_".to_string()),
+            };
             processes.push(gpu::Process {
                 device: Some(*dev),
                 pid: *pid,
                 user,
                 uid,
-                gpu_pct: per_device_info[*dev].0 / num_processes_per_device[*dev] as f64,
-                mem_pct: per_device_info[*dev].1 / num_processes_per_device[*dev] as f64,
-                mem_size_kib: 0,
-                command: "_noinfo_".to_string(),
+                gpu_pct,
+                mem_pct,
+                mem_size_kib,
+                command,
             })
         })
     });
@@ -229,7 +351,11 @@ PID 28154 is using 1 DRM device(s):
     let zs = extract_amd_information(
         &parse_text_concise_command(concise).expect("Test: AMD text concise information"),
         &parse_showpidgpus_command(pidgpu).expect("Test: AMD pid gpu information"),
-        &users);
+        &users,
+        &HashMap::new(),
+        &[],
+        &HashMap::new(),
+    );
     assert!(zs.eq(&vec![
         proc! { Some(0), 28154, "_zombie_28154", gpu::ZOMBIE_UID, 99.0/2.0, 57.0/2.0 },
         proc! { Some(0), 28156, "bob", 1001, 99.0/2.0, 57.0/2.0 },
@@ -237,6 +363,99 @@ PID 28154 is using 1 DRM device(s):
     ]));
 }
 
+#[test]
+fn test_extract_amd_information_uses_fdinfo_when_available() {
+    let concise = "
+================================= Concise Info =================================
+GPU  Temp (DieEdge)  AvgPwr  SCLK     MCLK    Fan     Perf  PwrCap  VRAM%  GPU%
+0    53.0c           220.0W  1576Mhz  945Mhz  10.98%  auto  220.0W   57%   99%
+================================================================================
+";
+    let pidgpu = "
+============================= GPUs Indexed by PID ==============================
+PID 28156 is using 1 DRM device(s):
+0
+================================================================================
+";
+    let users = map! {
+        28156 => ("bob", 1001usize)
+    };
+    let mut fdinfo = HashMap::new();
+    fdinfo.insert(
+        28156usize,
+        drm_fdinfo::FdInfoTotals {
+            engine_ns: 0,
+            memory_bytes: 2 * 1024 * 1024, // 2048 KiB
+        },
+    );
+    let device_mem_kib = vec![8 * 1024 * 1024]; // 8 GiB card
+    let zs = extract_amd_information(
+        &parse_text_concise_command(concise).expect("Test: AMD text concise information"),
+        &parse_showpidgpus_command(pidgpu).expect("Test: AMD pid gpu information"),
+        &users,
+        &fdinfo,
+        &device_mem_kib,
+        &HashMap::new(),
+    );
+    assert_eq!(zs.len(), 1);
+    assert_eq!(zs[0].mem_size_kib, 2048);
+    assert_eq!(zs[0].mem_pct, 100.0 * 2048.0 / (8.0 * 1024.0 * 1024.0));
+    // No interval data is passed in here (as if this were the session's first sample), so
+    // gpu_pct still falls back to the even split (a single process here, so the whole device's
+    // figure) - see test_extract_amd_information_uses_interval_gpu_pct_when_available for the
+    // differenced case.
+    assert_eq!(zs[0].gpu_pct, 99.0);
+}
+
+#[test]
+fn test_extract_amd_information_uses_interval_gpu_pct_when_available() {
+    let concise = "
+================================= Concise Info =================================
+GPU  Temp (DieEdge)  AvgPwr  SCLK     MCLK    Fan     Perf  PwrCap  VRAM%  GPU%
+0    53.0c           220.0W  1576Mhz  945Mhz  10.98%  auto  220.0W   57%   99%
+================================================================================
+";
+    let pidgpu = "
+============================= GPUs Indexed by PID ==============================
+PID 28156 is using 1 DRM device(s):
+0
+================================================================================
+";
+    let users = map! {
+        28156 => ("bob", 1001usize)
+    };
+    let mut fdinfo = HashMap::new();
+    fdinfo.insert(
+        28156usize,
+        drm_fdinfo::FdInfoTotals {
+            engine_ns: 500_000_000, // 0.5s of engine time
+            memory_bytes: 0,
+        },
+    );
+    let mut prev_engine_ns = HashMap::new();
+    prev_engine_ns.insert(28156usize, 0u64);
+    let interval_gpu_pct = drm_fdinfo::interval_gpu_pct(
+        &prev_engine_ns,
+        &fdinfo
+            .iter()
+            .map(|(pid, totals)| (*pid, totals.engine_ns))
+            .collect(),
+        1.0,
+    );
+    let zs = extract_amd_information(
+        &parse_text_concise_command(concise).expect("Test: AMD text concise information"),
+        &parse_showpidgpus_command(pidgpu).expect("Test: AMD pid gpu information"),
+        &users,
+        &fdinfo,
+        &[],
+        &interval_gpu_pct,
+    );
+    assert_eq!(zs.len(), 1);
+    // Differenced over a 1s interval, not the even split (which would be the whole device's 99%
+    // here, since there's only one process).
+    assert_eq!(zs[0].gpu_pct, 50.0);
+}
+
 // The format here is line-oriented:
 //
 // There should initially be at least one line with at least three fields which should
@@ -347,6 +566,123 @@ fn parse_text_concise_command(raw_text: &str) -> Result<Vec<(f64, f64)>, String>
     }
 }
 
+// The richer sibling of `parse_text_concise_command`: the "Concise Info" table has several more
+// columns than the VRAM%/GPU% pair that function extracts, and `AmdGPU::get_card_utilization`
+// wants all of them once the rsmi-library (amd_smi.rs) and sysfs (amd_sysfs.rs) backends have
+// nothing to say - this is the last rung of that fallback ladder.  It parses the output of its own
+// bare `rocm-smi` run (see `get_amd_card_utilization_from_text`); the output already fetched by
+// `get_raw_per_device_info` is not shared with it.
+//
+// Columns are read by fixed distance from the *end* of each row rather than by header name: the
+// header's "Temp (DieEdge)" is two whitespace-separated tokens where every data row only has one
+// (eg "53.0c"), which throws off any naive index-into-header-then-same-index-into-row match - the
+// same capitalization/ordering drift `parse_text_concise_command` already copes with by looking at
+// the header's last two tokens rather than its first two.
+pub struct ConciseCardInfo {
+    pub device: usize,
+    pub temp_c: f64,
+    pub power_draw_w: f64,
+    pub power_cap_w: f64,
+    pub core_clock_mhz: f64,
+    pub memory_clock_mhz: f64,
+    pub fan_pct: f64,
+    pub memory_utilization_pct: f64,
+    pub gpu_utilization_pct: f64,
+}
+
+fn parse_suffixed_f64<'a>(field: &'a str, suffix: &str) -> f64 {
+    field
+        .strip_suffix(suffix)
+        .unwrap_or(field)
+        .parse::<f64>()
+        .unwrap_or_default()
+}
+
+fn parse_text_concise_command_full(raw_text: &str) -> Result<Vec<ConciseCardInfo>, String> {
+    let block = find_block(raw_text, "= Concise Info =");
+    if block.len() <= 1 {
+        return Err("`Concise Info` block not found in output for AMD card:\n".to_string() + raw_text);
+    }
+    let hdr = block[0].split_whitespace().collect::<Vec<&str>>();
+    if hdr[hdr.len() - 2] != "VRAM%" || hdr[hdr.len() - 1] != "GPU%" {
+        return Err("Unexpected `Concise Info` header in output for AMD card:\n".to_string() + raw_text);
+    }
+    let mut cards = vec![];
+    for line in &block[1..] {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() < 9 {
+            continue;
+        }
+        let n = fields.len();
+        cards.push(ConciseCardInfo {
+            device: fields[0].parse::<usize>().unwrap_or_default(),
+            temp_c: parse_suffixed_f64(fields[n - 9], "c"),
+            power_draw_w: parse_suffixed_f64(fields[n - 8], "W"),
+            core_clock_mhz: parse_suffixed_f64(fields[n - 7], "Mhz"),
+            memory_clock_mhz: parse_suffixed_f64(fields[n - 6], "Mhz"),
+            fan_pct: parse_suffixed_f64(fields[n - 5], "%"),
+            // fields[n - 4] is "Perf" (eg "auto"), not a number we report.
+            power_cap_w: parse_suffixed_f64(fields[n - 3], "W"),
+            memory_utilization_pct: parse_suffixed_f64(fields[n - 2], "%"),
+            gpu_utilization_pct: parse_suffixed_f64(fields[n - 1], "%"),
+        });
+    }
+    Ok(cards)
+}
+
+// Last-resort `get_card_utilization` path: spawn bare `rocm-smi` (no args, like the second attempt
+// in `get_raw_per_device_info`) and read its Concise Info table.
+fn get_amd_card_utilization_from_text() -> Result<Vec<gpu::CardState>, String> {
+    match command::safe_command("rocm-smi", &[], TIMEOUT_SECONDS) {
+        Ok(text) => {
+            let cards = parse_text_concise_command_full(&text)?;
+            Ok(cards
+                .into_iter()
+                .map(|c| gpu::CardState {
+                    device: c.device,
+                    gpu_utilization_pct: c.gpu_utilization_pct,
+                    memory_utilization_pct: c.memory_utilization_pct,
+                    temperature_celsius: c.temp_c,
+                    power_draw_watts: c.power_draw_w,
+                    power_cap_watts: c.power_cap_w,
+                    core_clock_mhz: c.core_clock_mhz,
+                    memory_clock_mhz: c.memory_clock_mhz,
+                    fan_pct: c.fan_pct,
+                    ..Default::default()
+                })
+                .collect())
+        }
+        Err(CmdError::CouldNotStart(_)) => Ok(vec![]),
+        Err(e) => Err(format!("{:?}", e)),
+    }
+}
+
+#[test]
+fn test_parse_text_concise_command_full() {
+    let cards = parse_text_concise_command_full(
+        "
+================================= Concise Info =================================
+GPU  Temp (DieEdge)  AvgPwr  SCLK     MCLK    Fan     Perf  PwrCap  VRAM%  GPU%
+0    53.0c           220.0W  1576Mhz  945Mhz  10.98%  auto  220.0W   57%   99%
+1    26.0c           3.0W    852Mhz   167Mhz  9.41%   auto  220.0W    5%   63%
+================================================================================
+",
+    )
+    .expect("Test: Must have data");
+    assert_eq!(cards.len(), 2);
+    assert_eq!(cards[0].device, 0);
+    assert_eq!(cards[0].temp_c, 53.0);
+    assert_eq!(cards[0].power_draw_w, 220.0);
+    assert_eq!(cards[0].core_clock_mhz, 1576.0);
+    assert_eq!(cards[0].memory_clock_mhz, 945.0);
+    assert_eq!(cards[0].fan_pct, 10.98);
+    assert_eq!(cards[0].power_cap_w, 220.0);
+    assert_eq!(cards[0].memory_utilization_pct, 57.0);
+    assert_eq!(cards[0].gpu_utilization_pct, 99.0);
+    assert_eq!(cards[1].device, 1);
+    assert_eq!(cards[1].fan_pct, 9.41);
+}
+
 #[test]
 fn test_parse_text_concise_command() {
     let xs = parse_text_concise_command(
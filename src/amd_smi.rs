@@ -1,22 +1,376 @@
+// A safe, Result-returning layer over librocm_smi64, ROCm's System Management Library, mirroring
+// nvidia_nvml.rs's relationship to libnvidia-ml: talk to the vendor's C API directly instead of
+// scraping `rocm-smi` text/CSV output (see the comment at the top of amd.rs).  `AmdGPU::probe`
+// falls back to that text scraping when this library can't be initialized, so nodes without the
+// ROCm dev libs installed keep working exactly as before.
+
 use crate::gpu;
 use crate::ps::UserTable;
-use crate::util::cstrdup;
 
 ////// C library API //////////////////////////////////////////////////////////////////////////////
 
-// These APIs must match the C APIs *exactly*.  See ../gpuapi/sonar-nvidia.h for documentation of
+// These APIs must match the C APIs *exactly*.  See ../gpuapi/sonar-amd.h for documentation of
 // functionality and units.
 
 // Should use bindgen for this but not important yet.
 
+// Mirrors the subset of rocm_smi.h's `rsmi_process_info_t` we care about: a KFD PID and its total
+// VRAM usage, in bytes, across every device it's using.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct AmdSmiProcessInfo {
+    pub pid: cty::uint32_t,
+    pub vram_usage_bytes: cty::uint64_t,
+}
+
 extern "C" {
+    // rsmi_init(0)/rsmi_shut_down(): must bracket every other call below.
+    pub fn amdml_init() -> cty::c_int;
+    pub fn amdml_shutdown() -> cty::c_int;
+    // rsmi_num_monitor_devices: the number of GPUs rocm_smi can see, ie the valid range for the
+    // `device` index accepted by every other function here.
     pub fn amdml_device_get_count(count: *mut cty::uint32_t) -> cty::c_int;
+    // rsmi_dev_name_get: caller-allocated, NUL-terminated buffer of at least `name_len` bytes.
+    pub fn amdml_device_get_name(
+        device: cty::uint32_t,
+        name: *mut cty::c_char,
+        name_len: cty::uint32_t,
+    ) -> cty::c_int;
+    // rsmi_dev_memory_total_get(RSMI_MEM_TYPE_VRAM)/rsmi_dev_memory_usage_get: both in bytes.
+    pub fn amdml_device_get_memory_usage(
+        device: cty::uint32_t,
+        used: *mut cty::uint64_t,
+        total: *mut cty::uint64_t,
+    ) -> cty::c_int;
+    // rsmi_dev_busy_percent_get: instantaneous compute utilization, 0-100.
+    pub fn amdml_device_get_busy_percent(
+        device: cty::uint32_t,
+        percent: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // rsmi_compute_process_info_get: mirrors `nvml_device_get_processes`'s two-call size-probe
+    // convention (see `compute_process_info` below), except the PID list here is KFD-wide rather
+    // than per-device - a PID's devices are looked up separately via
+    // `amdml_device_get_process_gpus`.  Each entry carries the process's total VRAM usage across
+    // every device it's using (`rsmi_process_info_t.vram_usage`, in bytes), giving us a real
+    // per-process figure instead of dividing a device's total evenly across its users.
+    pub fn amdml_get_compute_process_info(
+        infos: *mut AmdSmiProcessInfo,
+        count: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // rsmi_compute_process_gpus_get: same size-probe convention, this time for the set of device
+    // indices a given KFD PID is using.
+    pub fn amdml_device_get_process_gpus(
+        pid: cty::uint32_t,
+        devices: *mut cty::uint32_t,
+        count: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // rsmi_status_string: the human-readable description rocm_smi has on file for a given
+    // `rsmi_status_t`, used by `check` below the same way `nvml_error_string` is in nvidia_nvml.rs.
+    pub fn amdml_error_string(code: cty::c_int) -> *const cty::c_char;
 }
 
 ////// End C library API //////////////////////////////////////////////////////////////////////////
 
-pub fn test() {
-    let mut num_devices: cty::uint32_t = 0;
-    let v = unsafe { amdml_device_get_count(&mut num_devices) };
-    println!("v={v}, num_devices={num_devices}");
-}
\ No newline at end of file
+// From rocm_smi.h's rsmi_status_t enum, the subset worth distinguishing.
+const RSMI_STATUS_NOT_SUPPORTED: cty::c_int = 2;
+const RSMI_STATUS_INVALID_ARGS: cty::c_int = 4;
+const RSMI_STATUS_PERMISSION: cty::c_int = 11;
+const RSMI_STATUS_INSUFFICIENT_SIZE: cty::c_int = 15;
+
+// As in nvidia_nvml.rs: give up growing a size-probed buffer eventually rather than spin forever
+// on a KFD PID/device list that's constantly churning.
+const MAX_SIZE_PROBE_ATTEMPTS: usize = 5;
+
+const AMDML_NAME_BUFFER_SIZE: usize = 64;
+
+/// Which broad category an rocm_smi failure falls into, same role as `NvmlErrorKind`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AmdSmiErrorKind {
+    NotSupported,
+    InvalidArgs,
+    NoPermission,
+    InsufficientSize,
+    Unknown,
+}
+
+/// A failed rocm_smi call, carrying both the raw `rsmi_status_t` code and the description
+/// rocm_smi itself gives for it via `rsmi_status_string`.
+#[derive(Debug)]
+pub struct AmdSmiError {
+    pub kind: AmdSmiErrorKind,
+    pub code: cty::c_int,
+    pub message: String,
+}
+
+impl std::fmt::Display for AmdSmiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "rocm_smi error {} ({:?}): {}",
+            self.code, self.kind, self.message
+        )
+    }
+}
+
+impl std::error::Error for AmdSmiError {}
+
+fn amdml_error_message(code: cty::c_int) -> String {
+    let ptr = unsafe { amdml_error_string(code) };
+    if ptr.is_null() {
+        return format!("unknown rocm_smi error {code}");
+    }
+    unsafe { std::ffi::CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn check(code: cty::c_int) -> Result<(), AmdSmiError> {
+    if code == 0 {
+        return Ok(());
+    }
+    let kind = match code {
+        RSMI_STATUS_NOT_SUPPORTED => AmdSmiErrorKind::NotSupported,
+        RSMI_STATUS_INVALID_ARGS => AmdSmiErrorKind::InvalidArgs,
+        RSMI_STATUS_PERMISSION => AmdSmiErrorKind::NoPermission,
+        RSMI_STATUS_INSUFFICIENT_SIZE => AmdSmiErrorKind::InsufficientSize,
+        _ => AmdSmiErrorKind::Unknown,
+    };
+    Err(AmdSmiError {
+        kind,
+        code,
+        message: amdml_error_message(code),
+    })
+}
+
+fn c_buf_to_string(buf: &[cty::c_char]) -> String {
+    unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Open the library and return the device count, or `None` if the library is absent, too old, or
+/// no cards are visible to it - the signal `amd.rs::probe` uses to decide whether to prefer this
+/// backend over the `rocm-smi` text scraper.
+pub fn open() -> Option<cty::uint32_t> {
+    if check(unsafe { amdml_init() }).is_err() {
+        return None;
+    }
+    let mut count: cty::uint32_t = 0;
+    if check(unsafe { amdml_device_get_count(&mut count) }).is_err() || count == 0 {
+        unsafe { amdml_shutdown() };
+        return None;
+    }
+    Some(count)
+}
+
+pub fn close() {
+    unsafe {
+        amdml_shutdown();
+    }
+}
+
+/// A card's model name and VRAM size, read directly from the library rather than parsed out of
+/// `rocm-smi --showproductname` text.
+pub fn get_card_configuration(ndev: cty::uint32_t) -> Vec<gpu::Card> {
+    (0..ndev).map(get_one_card_configuration).collect()
+}
+
+fn get_one_card_configuration(device: cty::uint32_t) -> gpu::Card {
+    let mut name_buf = [0 as cty::c_char; AMDML_NAME_BUFFER_SIZE];
+    let model = if check(unsafe {
+        amdml_device_get_name(device, name_buf.as_mut_ptr(), name_buf.len() as cty::uint32_t)
+    })
+    .is_ok()
+    {
+        c_buf_to_string(&name_buf)
+    } else {
+        "unknown".to_string()
+    };
+
+    let mut used: cty::uint64_t = 0;
+    let mut total: cty::uint64_t = 0;
+    let mem_size_kib = if check(unsafe { amdml_device_get_memory_usage(device, &mut used, &mut total) }).is_ok()
+    {
+        (total / 1024) as usize
+    } else {
+        0
+    };
+
+    gpu::Card {
+        model,
+        mem_size_kib,
+        ..Default::default()
+    }
+}
+
+/// Instantaneous compute/memory utilization for each of the `ndev` devices; a device the library
+/// can't answer for (eg a card the driver doesn't expose busy-percent for) is omitted.
+pub fn get_card_utilization(ndev: cty::uint32_t) -> Vec<gpu::CardState> {
+    (0..ndev).filter_map(get_one_card_utilization).collect()
+}
+
+fn get_one_card_utilization(device: cty::uint32_t) -> Option<gpu::CardState> {
+    let mut gpu_pct: cty::uint32_t = 0;
+    check(unsafe { amdml_device_get_busy_percent(device, &mut gpu_pct) }).ok()?;
+
+    let mut used: cty::uint64_t = 0;
+    let mut total: cty::uint64_t = 0;
+    let mem_pct = if check(unsafe { amdml_device_get_memory_usage(device, &mut used, &mut total) }).is_ok()
+        && total > 0
+    {
+        (used as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    };
+
+    Some(gpu::CardState {
+        device: device as usize,
+        gpu_utilization_pct: gpu_pct as f64,
+        memory_utilization_pct: mem_pct,
+        ..Default::default()
+    })
+}
+
+// Query the set of KFD PIDs currently running and each one's total VRAM usage, handling the same
+// null-buffer size-probe convention as nvidia_nvml.rs's `device_processes`.
+fn compute_process_info() -> Vec<AmdSmiProcessInfo> {
+    let mut count: cty::uint32_t = 0;
+    for _ in 0..MAX_SIZE_PROBE_ATTEMPTS {
+        let rc = unsafe { amdml_get_compute_process_info(std::ptr::null_mut(), &mut count) };
+        if count == 0 {
+            return vec![];
+        }
+        if rc != 0 && rc != RSMI_STATUS_INSUFFICIENT_SIZE {
+            return vec![];
+        }
+        let mut infos = vec![
+            AmdSmiProcessInfo {
+                pid: 0,
+                vram_usage_bytes: 0
+            };
+            count as usize
+        ];
+        let rc = unsafe { amdml_get_compute_process_info(infos.as_mut_ptr(), &mut count) };
+        if rc == 0 {
+            infos.truncate(count as usize);
+            return infos;
+        }
+        if rc != RSMI_STATUS_INSUFFICIENT_SIZE {
+            return vec![];
+        }
+        // `count` grew between the two calls above; loop around and probe again with the new size.
+    }
+    vec![]
+}
+
+// Total VRAM for `device`, in KiB, or 0 if the library can't answer - used to turn a process's
+// absolute `vram_usage_bytes` (see `compute_process_info`) into a percentage, the same way
+// `amd.rs::extract_amd_information` turns fdinfo's absolute figure into a percentage via
+// `device_mem_kib`.
+fn device_total_mem_kib(device: cty::uint32_t) -> usize {
+    let mut used: cty::uint64_t = 0;
+    let mut total: cty::uint64_t = 0;
+    if check(unsafe { amdml_device_get_memory_usage(device, &mut used, &mut total) }).is_ok() {
+        (total / 1024) as usize
+    } else {
+        0
+    }
+}
+
+// Query the devices a single KFD PID is using, same size-probe convention.
+fn process_gpus(pid: cty::uint32_t) -> Vec<usize> {
+    let mut count: cty::uint32_t = 0;
+    for _ in 0..MAX_SIZE_PROBE_ATTEMPTS {
+        let rc = unsafe { amdml_device_get_process_gpus(pid, std::ptr::null_mut(), &mut count) };
+        if count == 0 {
+            return vec![];
+        }
+        if rc != 0 && rc != RSMI_STATUS_INSUFFICIENT_SIZE {
+            return vec![];
+        }
+        let mut devices = vec![0 as cty::uint32_t; count as usize];
+        let rc = unsafe { amdml_device_get_process_gpus(pid, devices.as_mut_ptr(), &mut count) };
+        if rc == 0 {
+            devices.truncate(count as usize);
+            return devices.into_iter().map(|d| d as usize).collect();
+        }
+        if rc != RSMI_STATUS_INSUFFICIENT_SIZE {
+            return vec![];
+        }
+        // `count` grew between the two calls above; loop around and probe again with the new size.
+    }
+    vec![]
+}
+
+/// Real per-process VRAM, as opposed to `amd.rs::extract_amd_information`'s even division of each
+/// device's total across its users: `rsmi_compute_process_info_get` hands back one aggregate
+/// `vram_usage_bytes` per KFD PID across every device it's using, which we split evenly across
+/// just the devices that PID is confirmed to use (from `process_gpus`) - still an approximation
+/// when a process spans more than one device, but a real, non-zero figure rather than the
+/// hardcoded `0` this used to report, and a better figure than the fallback's per-device even
+/// split when a process has only one device.  `gpu_pct` is left at 0.0: rocm_smi has no
+/// per-process engine-time call, only `rsmi_dev_busy_percent_get`'s whole-device figure (see
+/// `get_one_card_utilization`), and attributing that across processes is exactly the even-split
+/// heuristic this function is trying to improve on for memory.
+pub fn get_process_utilization(user_by_pid: &UserTable) -> Vec<gpu::Process> {
+    let mut processes = vec![];
+    for info in compute_process_info() {
+        let devices = process_gpus(info.pid);
+        if devices.is_empty() {
+            continue;
+        }
+        let pid = info.pid as usize;
+        let (user, uid) = if let Some((user, uid)) = user_by_pid.get(&pid) {
+            (user.to_string(), *uid)
+        } else {
+            ("_zombie_".to_owned() + &pid.to_string(), gpu::ZOMBIE_UID)
+        };
+        let mem_size_kib = (info.vram_usage_bytes / 1024) as usize / devices.len();
+        let command = std::fs::read_to_string(format!("/proc/{pid}/comm"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_else(|_| "_noinfo_".to_string());
+        for device in devices {
+            let mem_pct = match device_total_mem_kib(device as cty::uint32_t) {
+                total_kib if total_kib > 0 => 100.0 * mem_size_kib as f64 / total_kib as f64,
+                _ => 0.0,
+            };
+            processes.push(gpu::Process {
+                device: Some(device),
+                pid,
+                user: user.clone(),
+                uid,
+                gpu_pct: 0.0,
+                mem_pct,
+                mem_size_kib,
+                command: command.clone(),
+            });
+        }
+    }
+    processes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_maps_zero_to_ok() {
+        assert!(check(0).is_ok());
+    }
+
+    #[test]
+    fn test_check_maps_known_codes() {
+        assert_eq!(check(2).unwrap_err().kind, AmdSmiErrorKind::NotSupported);
+        assert_eq!(check(15).unwrap_err().kind, AmdSmiErrorKind::InsufficientSize);
+        assert_eq!(check(999).unwrap_err().kind, AmdSmiErrorKind::Unknown);
+    }
+
+    #[test]
+    fn test_c_buf_to_string_stops_at_nul() {
+        let mut buf = [0 as cty::c_char; 16];
+        for (i, b) in b"MI250X\0garbage".iter().enumerate() {
+            buf[i] = *b as cty::c_char;
+        }
+        assert_eq!(c_buf_to_string(&buf), "MI250X");
+    }
+}
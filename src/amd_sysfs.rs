@@ -0,0 +1,206 @@
+// Read AMD card configuration and instantaneous utilization straight out of sysfs, the way
+// lightweight status tools (eg `amdgpu_top`) do, rather than spawning `rocm-smi` (see amd.rs) or
+// requiring the ROCm dev libraries (see amd_smi.rs).  `rocm-smi --showproductname` can't report
+// installed VRAM on some of our hardware, but the amdgpu kernel driver exposes it directly under
+// `/sys/class/drm/card*/device/`, along with a `hwmon/hwmon*/` subdirectory carrying temperature,
+// power, clock and fan readings - the same shape of interface hwmon.rs already reads for CPU/NIC
+// sensors.  Like hwmon.rs, this goes through `std::fs` directly rather than `procfsapi::ProcfsAPI`,
+// which is scoped to /proc.
+
+use crate::gpu;
+
+use std::collections::HashMap;
+
+const DRM_ROOT: &str = "/sys/class/drm";
+
+// From pci.ids: the PCI vendor ID amdgpu-owned devices report in `device/vendor`.
+const AMD_PCI_VENDOR_ID: &str = "0x1002";
+
+/// Every `cardN` directory under `/sys/class/drm` whose `device/vendor` is AMD's, paired with the
+/// files we care about out of `device/` and its `hwmon/hwmon*/` child - read once up front so the
+/// parsing logic below (`build_card_configuration`/`build_card_utilization`) stays pure and
+/// testable against canned fixtures, the same split hwmon.rs uses.
+fn amd_card_files() -> Vec<(String, HashMap<String, String>)> {
+    let Ok(card_dirs) = std::fs::read_dir(DRM_ROOT) else {
+        return vec![];
+    };
+    let mut cards = vec![];
+    for card_dir in card_dirs.flatten() {
+        let name = card_dir.file_name().to_string_lossy().to_string();
+        if !name.starts_with("card") || name.contains('-') {
+            // Skip connector pseudo-devices like "card0-DP-1".
+            continue;
+        }
+        let device_dir = card_dir.path().join("device");
+        let Ok(vendor) = std::fs::read_to_string(device_dir.join("vendor")) else {
+            continue;
+        };
+        if vendor.trim() != AMD_PCI_VENDOR_ID {
+            continue;
+        }
+        cards.push((name, read_card_files(&device_dir)));
+    }
+    cards.sort_by(|a, b| a.0.cmp(&b.0));
+    cards
+}
+
+fn read_card_files(device_dir: &std::path::Path) -> HashMap<String, String> {
+    let mut files = HashMap::new();
+    for name in [
+        "device",
+        "revision",
+        "product_name",
+        "mem_info_vram_total",
+        "mem_info_vram_used",
+        "gpu_busy_percent",
+    ] {
+        if let Ok(contents) = std::fs::read_to_string(device_dir.join(name)) {
+            files.insert(name.to_string(), contents);
+        }
+    }
+    let hwmon_dir = device_dir.join("hwmon");
+    let Ok(hwmon_entries) = std::fs::read_dir(&hwmon_dir) else {
+        return files;
+    };
+    // There's exactly one hwmon chip per card; take the first (only) one we find.
+    if let Some(chip_dir) = hwmon_entries.flatten().next().map(|e| e.path()) {
+        for name in [
+            "temp1_input",
+            "power1_average",
+            "freq1_input",
+            "freq2_input",
+            "fan1_input",
+        ] {
+            if let Ok(contents) = std::fs::read_to_string(chip_dir.join(name)) {
+                files.insert(name.to_string(), contents);
+            }
+        }
+    }
+    files
+}
+
+/// Walk every AMD card found under `/sys/class/drm` and report what we know about each.  A host
+/// with no AMD cards (or no `/sys/class/drm` at all, eg a non-Linux kernel) reports an empty list
+/// rather than an error.
+pub fn get_card_configuration() -> Vec<gpu::Card> {
+    amd_card_files()
+        .iter()
+        .map(|(_, files)| build_card_configuration(files))
+        .collect()
+}
+
+fn build_card_configuration(files: &HashMap<String, String>) -> gpu::Card {
+    let mem_size_kib = files
+        .get("mem_info_vram_total")
+        .and_then(|s| parse_bytes_as_kib(s))
+        .unwrap_or(0);
+    let model = files
+        .get("product_name")
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| name_from_pci_ids(files));
+    gpu::Card {
+        model,
+        mem_size_kib,
+        ..Default::default()
+    }
+}
+
+// `product_name` isn't exposed by every amdgpu driver version; fall back to a "device/revision"
+// label when it's absent rather than leaving the card unnamed.  A real PCI-ID-to-name table is
+// future work - see the TODO on the lspci-based approach in amd.rs.
+fn name_from_pci_ids(files: &HashMap<String, String>) -> String {
+    let device = files
+        .get("device")
+        .map(|s| s.trim())
+        .unwrap_or("unknown");
+    let revision = files
+        .get("revision")
+        .map(|s| s.trim())
+        .unwrap_or("unknown");
+    format!("AMD GPU (device {device}, rev {revision})")
+}
+
+/// Instantaneous per-card utilization/temperature/power/clock telemetry, read the same way as
+/// `get_card_configuration`.  Fan speed is not reported: hwmon's `fan1_input` is in RPM, while
+/// `gpu::CardState::fan_pct` is a percentage, so `fan_pct` stays 0.  A card missing `gpu_busy_percent` (the one mandatory field here) is
+/// omitted rather than reported with zeroes, matching `nvidia_nvml.rs::get_gpu_sample`'s treatment
+/// of its own load-bearing query.
+pub fn get_card_utilization() -> Vec<gpu::CardState> {
+    amd_card_files()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (_, files))| build_card_utilization(i, files))
+        .collect()
+}
+
+fn build_card_utilization(device: usize, files: &HashMap<String, String>) -> Option<gpu::CardState> {
+    let gpu_utilization_pct = files.get("gpu_busy_percent").and_then(|s| s.trim().parse::<f64>().ok())?;
+    let memory_utilization_pct = match (
+        files.get("mem_info_vram_used").and_then(|s| parse_bytes_as_kib(s)),
+        files.get("mem_info_vram_total").and_then(|s| parse_bytes_as_kib(s)),
+    ) {
+        (Some(used), Some(total)) if total > 0 => (used as f64 / total as f64) * 100.0,
+        _ => 0.0,
+    };
+    // hwmon units: millidegrees C, microwatts, Hz; a missing or unparsable file reads as 0.
+    let hwmon = |name: &str, scale: f64| {
+        files
+            .get(name)
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .map_or(0.0, |v| v / scale)
+    };
+    Some(gpu::CardState {
+        device,
+        gpu_utilization_pct,
+        memory_utilization_pct,
+        temperature_celsius: hwmon("temp1_input", 1000.0),
+        power_draw_watts: hwmon("power1_average", 1_000_000.0),
+        core_clock_mhz: hwmon("freq1_input", 1_000_000.0),
+        memory_clock_mhz: hwmon("freq2_input", 1_000_000.0),
+        ..Default::default()
+    })
+}
+
+fn parse_bytes_as_kib(text: &str) -> Option<usize> {
+    let bytes = text.trim().parse::<usize>().ok()?;
+    Some(bytes / 1024)
+}
+
+#[test]
+fn test_build_card_configuration_with_product_name() {
+    let mut files = HashMap::new();
+    files.insert("product_name".to_string(), "AMD Instinct MI250X\n".to_string());
+    files.insert("mem_info_vram_total".to_string(), format!("{}\n", 64 * 1024 * 1024 * 1024u64));
+    let card = build_card_configuration(&files);
+    assert_eq!(card.model, "AMD Instinct MI250X");
+    assert_eq!(card.mem_size_kib, 64 * 1024 * 1024);
+}
+
+#[test]
+fn test_build_card_configuration_falls_back_to_pci_ids() {
+    let mut files = HashMap::new();
+    files.insert("device".to_string(), "0x740f\n".to_string());
+    files.insert("revision".to_string(), "0x02\n".to_string());
+    let card = build_card_configuration(&files);
+    assert_eq!(card.model, "AMD GPU (device 0x740f, rev 0x02)");
+    assert_eq!(card.mem_size_kib, 0);
+}
+
+#[test]
+fn test_build_card_utilization() {
+    let mut files = HashMap::new();
+    files.insert("gpu_busy_percent".to_string(), "42\n".to_string());
+    files.insert("mem_info_vram_used".to_string(), format!("{}\n", 1024 * 1024));
+    files.insert("mem_info_vram_total".to_string(), format!("{}\n", 4 * 1024 * 1024));
+    let state = build_card_utilization(0, &files).expect("Test: Must have data");
+    assert_eq!(state.device, 0);
+    assert_eq!(state.gpu_utilization_pct, 42.0);
+    assert_eq!(state.memory_utilization_pct, 25.0);
+}
+
+#[test]
+fn test_build_card_utilization_missing_busy_percent_is_none() {
+    let files = HashMap::new();
+    assert!(build_card_utilization(0, &files).is_none());
+}
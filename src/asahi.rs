@@ -0,0 +1,174 @@
+// Get info about the Apple AGX GPU (M1/M2-class integrated GPUs: G13G/G13S/G13C/G13D/G14G) exposed
+// by Linux's `drm/asahi` driver on Apple Silicon hardware running the Asahi Linux distribution.
+// There is no vendor CLI to shell out to here (unlike amd.rs's `rocm-smi` or NVIDIA's NVML), so
+// this backend is sysfs/fdinfo-only, same shape as amd.rs: a `probe()` that
+// returns a `gpu::GPU` trait object, backed by `get_card_configuration`/`get_process_utilization`/
+// `get_card_utilization`.
+
+use crate::drm_fdinfo;
+use crate::gpu;
+use crate::ps::UserTable;
+
+use std::path::Path;
+
+const DRM_DRIVER: &str = "asahi";
+
+pub struct AsahiGPU {}
+
+pub fn probe() -> Option<Box<dyn gpu::GPU>> {
+    if asahi_present() {
+        Some(Box::new(AsahiGPU {}))
+    } else {
+        None
+    }
+}
+
+// `/sys/module/asahi` exists iff the `asahi` DRM driver is loaded, the same style of presence
+// check `amd.rs::amd_present` makes for `amdgpu`.  Fall back to checking whether any `/sys/class/
+// drm/cardN/device/driver` symlink resolves to a driver named "asahi", in case the module is
+// compiled in rather than loaded as a module (so has no `/sys/module/asahi` entry of its own).
+fn asahi_present() -> bool {
+    if Path::new("/sys/module/asahi").exists() {
+        return true;
+    }
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with("card") || name.contains('-') {
+            return false;
+        }
+        driver_name(&entry.path().join("device")).as_deref() == Some(DRM_DRIVER)
+    })
+}
+
+fn driver_name(device_dir: &Path) -> Option<String> {
+    let link = std::fs::read_link(device_dir.join("driver")).ok()?;
+    link.file_name()?.to_str().map(|s| s.to_string())
+}
+
+impl gpu::GPU for AsahiGPU {
+    fn get_manufacturer(&mut self) -> String {
+        "Apple".to_string()
+    }
+
+    fn get_card_configuration(&mut self) -> Result<Vec<gpu::Card>, String> {
+        Ok(vec![gpu::Card {
+            model: agx_model_name(),
+            ..Default::default()
+        }])
+    }
+
+    fn get_process_utilization(
+        &mut self,
+        user_by_pid: &UserTable,
+    ) -> Result<Vec<gpu::Process>, String> {
+        Ok(get_asahi_process_utilization(user_by_pid))
+    }
+
+    fn get_card_utilization(&mut self) -> Result<Vec<gpu::CardState>, String> {
+        Ok(get_asahi_card_utilization())
+    }
+}
+
+// The GPU's model (eg "apple,agx-g13g") is one of the entries in the device tree's top-level
+// `compatible` string, a NUL-separated list of strings from most to least specific; we want the
+// first `apple,agx-*` entry, since the board-level entries ahead of it (eg "apple,j274") name the
+// machine, not the GPU.
+fn agx_model_name() -> String {
+    let Ok(compatible) = std::fs::read_to_string("/proc/device-tree/compatible") else {
+        return "Apple AGX GPU (unknown model)".to_string();
+    };
+    compatible
+        .split('\0')
+        .find(|s| s.starts_with("apple,agx"))
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "Apple AGX GPU (unknown model)".to_string())
+}
+
+#[test]
+fn test_agx_model_name_parses_compatible_list() {
+    // Mirrors the pure-function-over-canned-fixture style of hwmon.rs/amd_sysfs.rs: the real
+    // implementation reads /proc/device-tree/compatible, which isn't something we can fake a path
+    // for in a unit test, so this just documents the expected NUL-separated shape via the same
+    // splitting logic inline.
+    let compatible = "apple,j314s\0apple,arm-platform\0";
+    assert!(compatible.split('\0').find(|s| s.starts_with("apple,agx")).is_none());
+    let compatible = "apple,j314s\0apple,agx-g13g\0apple,arm-platform\0";
+    assert_eq!(
+        compatible.split('\0').find(|s| s.starts_with("apple,agx")),
+        Some("apple,agx-g13g")
+    );
+}
+
+// Instantaneous GPU frequency/power, read from the driver's devfreq node
+// (`/sys/class/devfreq/*.gpu/`) the way the Asahi community's own monitoring tools do: `cur_freq`
+// is the current GPU core clock in Hz, and `device/power1_average` under the matching hwmon chip
+// (if one is registered) gives instantaneous power draw in microwatts.  GPU and memory
+// utilization are not read, so `gpu_utilization_pct`/`memory_utilization_pct` stay 0.  There's
+// exactly one AGX GPU per machine today, so this always reports device 0 when present.
+fn get_asahi_card_utilization() -> Vec<gpu::CardState> {
+    let Some(devfreq_dir) = find_gpu_devfreq_dir() else {
+        return vec![];
+    };
+    let clock_mhz = std::fs::read_to_string(devfreq_dir.join("cur_freq"))
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .map(|hz| (hz / 1_000_000) as f64)
+        .unwrap_or(0.0);
+    let power_draw_watts = find_gpu_hwmon_power(&devfreq_dir).unwrap_or(0.0);
+    vec![gpu::CardState {
+        device: 0,
+        core_clock_mhz: clock_mhz,
+        power_draw_watts,
+        ..Default::default()
+    }]
+}
+
+fn find_gpu_devfreq_dir() -> Option<std::path::PathBuf> {
+    let entries = std::fs::read_dir("/sys/class/devfreq").ok()?;
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.to_string_lossy().contains("gpu"))
+}
+
+fn find_gpu_hwmon_power(devfreq_dir: &Path) -> Option<f64> {
+    let hwmon_dir = devfreq_dir.join("device/hwmon");
+    let entries = std::fs::read_dir(hwmon_dir).ok()?;
+    let chip_dir = entries.flatten().next()?.path();
+    let microwatts = std::fs::read_to_string(chip_dir.join("power1_average"))
+        .ok()?
+        .trim()
+        .parse::<f64>()
+        .ok()?;
+    Some(microwatts / 1_000_000.0)
+}
+
+// Per-process attribution comes entirely from DRM fdinfo (see drm_fdinfo.rs) - there's no vendor
+// CLI to ask instead.  `gpu_pct` is left at 0 here since it requires differencing two
+// `drm-engine-*` readings over a known interval, the same way `procfs::interval_cpu_pct` needs two
+// `cpu_time_ticks` snapshots; callers that want that figure should snapshot
+// `drm_fdinfo::engine_ns_snapshot` across sampling rounds and feed it to `interval_gpu_pct`.
+fn get_asahi_process_utilization(user_by_pid: &UserTable) -> Vec<gpu::Process> {
+    let mut processes = vec![];
+    for (&pid, (user, uid)) in user_by_pid.iter() {
+        let Some(totals) = drm_fdinfo::read_process_totals(pid, DRM_DRIVER) else {
+            continue;
+        };
+        processes.push(gpu::Process {
+            device: Some(0),
+            pid,
+            user: user.to_string(),
+            uid: *uid,
+            gpu_pct: 0.0,
+            mem_pct: 0.0,
+            mem_size_kib: (totals.memory_bytes / 1024) as usize,
+            command: std::fs::read_to_string(format!("/proc/{pid}/comm"))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|_| "_noinfo_".to_string()),
+        });
+    }
+    processes
+}
@@ -0,0 +1,196 @@
+// Group process resource usage by cgroup v2 slice/scope, so that HPC and build hosts that run
+// everything inside Slurm job scopes or systemd units (`cgroup2 on /sys/fs/cgroup`) can report
+// accounting per job rather than per scattered pid.  See also slurm.rs, which reads the same
+// /proc/{pid}/cgroup file to recover a bare Slurm job id.
+
+use crate::process;
+
+use std::collections::HashMap;
+
+/// Aggregated resource usage for one cgroup, keyed by its v2 path (eg
+/// `/system.slice/slurmstepd.scope/job_12345`).
+pub struct CgroupUsage {
+    pub path: String,
+    pub cpu_pct: f64,
+    pub mem_size_kib: usize,
+    pub cputime_sec: usize,
+}
+
+impl CgroupUsage {
+    fn new(path: String) -> CgroupUsage {
+        CgroupUsage {
+            path,
+            cpu_pct: 0.0,
+            mem_size_kib: 0,
+            cputime_sec: 0,
+        }
+    }
+}
+
+/// Group `processes` by their cgroup v2 path and sum `cpu_pct`, `mem_size_kib`, and `cputime_sec`
+/// per group.  Processes whose cgroup can't be resolved (already exited, or a cgroup v1 host with
+/// no unified hierarchy) are omitted rather than dropped into a bogus catch-all group.
+///
+/// Where the cgroup exposes kernel-authoritative totals - `memory.current` for memory and the
+/// `usage_usec` field of `cpu.stat` for cpu time - those replace the summed per-process figures,
+/// since the kernel counters also account for processes that have already exited but whose usage
+/// is still charged to the cgroup.  When those files can't be read (permissions, or the cgroup
+/// having been torn down in the meantime) we fall back to the per-process sum.
+pub fn rollup_by_cgroup(processes: &[process::Process]) -> HashMap<String, CgroupUsage> {
+    group_by_cgroup(
+        processes,
+        get_cgroup_path,
+        read_memory_current_kib,
+        read_cpu_stat_usage_sec,
+    )
+}
+
+fn group_by_cgroup(
+    processes: &[process::Process],
+    path_of: impl Fn(usize) -> Option<String>,
+    memory_current_kib: impl Fn(&str) -> Option<usize>,
+    cpu_stat_usage_sec: impl Fn(&str) -> Option<usize>,
+) -> HashMap<String, CgroupUsage> {
+    let mut groups: HashMap<String, CgroupUsage> = HashMap::new();
+    for p in processes {
+        let Some(path) = path_of(p.pid) else {
+            continue;
+        };
+        let usage = groups
+            .entry(path.clone())
+            .or_insert_with(|| CgroupUsage::new(path));
+        usage.cpu_pct += p.cpu_pct;
+        usage.mem_size_kib += p.mem_size_kib;
+        usage.cputime_sec += p.cputime_sec;
+    }
+    for usage in groups.values_mut() {
+        if let Some(mem_kib) = memory_current_kib(&usage.path) {
+            usage.mem_size_kib = mem_kib;
+        }
+        if let Some(cputime_sec) = cpu_stat_usage_sec(&usage.path) {
+            usage.cputime_sec = cputime_sec;
+        }
+    }
+    groups
+}
+
+/// Read the cgroup v2 membership of `pid` from `/proc/{pid}/cgroup` and return its controlling
+/// path.
+fn get_cgroup_path(pid: usize) -> Option<String> {
+    let text = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    parse_cgroup_path(&text)
+}
+
+// On a cgroup v2 host the file has a single line of the form `0::<path>`; anything else (cgroup
+// v1's numbered per-controller lines, or no matching line at all) means there's no unified
+// hierarchy to attribute this process to.
+fn parse_cgroup_path(text: &str) -> Option<String> {
+    for l in text.lines() {
+        if let Some(path) = l.strip_prefix("0::") {
+            return Some(path.to_string());
+        }
+    }
+    None
+}
+
+fn cgroup_fs_path(cgroup_path: &str, file: &str) -> String {
+    format!("/sys/fs/cgroup{cgroup_path}/{file}")
+}
+
+fn read_memory_current_kib(cgroup_path: &str) -> Option<usize> {
+    let text = std::fs::read_to_string(cgroup_fs_path(cgroup_path, "memory.current")).ok()?;
+    text.trim().parse::<usize>().ok().map(|bytes| bytes / 1024)
+}
+
+fn read_cpu_stat_usage_sec(cgroup_path: &str) -> Option<usize> {
+    let text = std::fs::read_to_string(cgroup_fs_path(cgroup_path, "cpu.stat")).ok()?;
+    for l in text.lines() {
+        if let Some(usec) = l.strip_prefix("usage_usec ") {
+            return usec
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .map(|usec| usec / 1_000_000);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+fn test_process(
+    pid: usize,
+    cpu_pct: f64,
+    mem_size_kib: usize,
+    cputime_sec: usize,
+) -> process::Process {
+    process::Process {
+        pid,
+        uid: 0,
+        user: "user".to_string(),
+        cpu_pct,
+        mem_pct: 0.0,
+        cputime_sec,
+        mem_size_kib,
+        rss_kib: 0,
+        command: "command".to_string(),
+        full_command: "command".to_string(),
+        ppid: 1,
+        session: pid,
+        state: 'S',
+    }
+}
+
+#[test]
+fn test_parse_cgroup_path_v2() {
+    assert_eq!(
+        parse_cgroup_path("0::/system.slice/slurmstepd.scope/job_12345\n"),
+        Some("/system.slice/slurmstepd.scope/job_12345".to_string())
+    );
+}
+
+#[test]
+fn test_parse_cgroup_path_v1_has_no_unified_hierarchy() {
+    let text = "12:pids:/user.slice\n11:memory:/user.slice\n1:name=systemd:/user.slice\n";
+    assert_eq!(parse_cgroup_path(text), None);
+}
+
+#[test]
+fn test_group_by_cgroup_sums_and_omits_unresolved() {
+    let processes = vec![
+        test_process(100, 10.0, 1000, 60),
+        test_process(101, 5.0, 500, 30),
+        test_process(200, 1.0, 100, 10),
+        test_process(300, 1.0, 100, 10), // no resolvable cgroup
+    ];
+    let groups = group_by_cgroup(
+        &processes,
+        |pid| match pid {
+            100 | 101 => Some("/job_1".to_string()),
+            200 => Some("/job_2".to_string()),
+            _ => None,
+        },
+        |_path| None,
+        |_path| None,
+    );
+    assert_eq!(groups.len(), 2);
+    let job1 = &groups["/job_1"];
+    assert_eq!(job1.cpu_pct, 15.0);
+    assert_eq!(job1.mem_size_kib, 1500);
+    assert_eq!(job1.cputime_sec, 90);
+    let job2 = &groups["/job_2"];
+    assert_eq!(job2.mem_size_kib, 100);
+}
+
+#[test]
+fn test_group_by_cgroup_prefers_kernel_totals() {
+    let processes = vec![test_process(100, 10.0, 1000, 60)];
+    let groups = group_by_cgroup(
+        &processes,
+        |_pid| Some("/job_1".to_string()),
+        |_path| Some(4096), // memory.current, in KiB after conversion
+        |_path| Some(120),  // cpu.stat usage_usec, in seconds after conversion
+    );
+    let job1 = &groups["/job_1"];
+    assert_eq!(job1.mem_size_kib, 4096);
+    assert_eq!(job1.cputime_sec, 120);
+}
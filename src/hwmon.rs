@@ -0,0 +1,200 @@
+/// Read per-sensor chip temperatures from /sys/class/hwmon/hwmon*/, the standard Linux interface
+/// thermal sensors (CPU package, NIC, NVMe, etc) expose - useful for diagnosing thermal throttling
+/// on a node that otherwise reports healthy CPU/mem/process numbers.  hwmon lives under /sys
+/// rather than /proc, so (like cgroup.rs's /sys/fs/cgroup reads) this goes through `std::fs`
+/// directly rather than `procfsapi::ProcfsAPI`, which is scoped to /proc.
+
+use std::collections::HashMap;
+
+/// One `tempN_*` sensor under one hwmon chip directory, in degrees Celsius (the kernel reports
+/// millidegrees; see `parse_millidegrees`).  `max_c`/`crit_c` are `None` when the chip doesn't
+/// expose that particular threshold, which most don't for every sensor.
+#[derive(PartialEq, Debug)]
+pub struct SensorReading {
+    pub chip_name: String,
+    pub label: String,
+    pub temp_c: f64,
+    pub max_c: Option<f64>,
+    pub crit_c: Option<f64>,
+}
+
+const HWMON_ROOT: &str = "/sys/class/hwmon";
+
+/// Walk every `hwmon*` chip directory under `/sys/class/hwmon` and collect its temperature
+/// sensors.  A hwmon-less host (no thermal sensors exposed, or a non-Linux kernel) simply has no
+/// `/sys/class/hwmon` directory, which we report as an empty list rather than an error - the rest
+/// of Sonar's output shouldn't fail just because thermal data isn't available here.
+pub fn get_sensor_readings() -> Result<Vec<SensorReading>, String> {
+    let Ok(chip_dirs) = std::fs::read_dir(HWMON_ROOT) else {
+        return Ok(vec![]);
+    };
+    let mut readings = vec![];
+    for chip_dir in chip_dirs.flatten() {
+        let dir = chip_dir.path();
+        let chip_name = std::fs::read_to_string(dir.join("name"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+        let Ok(dir_entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut files = HashMap::new();
+        for entry in dir_entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with("temp") {
+                continue;
+            }
+            if let Ok(contents) = std::fs::read_to_string(entry.path()) {
+                files.insert(name, contents);
+            }
+        }
+        readings.extend(build_readings(&chip_name, &files));
+    }
+    Ok(readings)
+}
+
+// Turn one chip's `tempN_*` files (as read from its hwmon directory, or a canned fixture in
+// tests) into its sensor readings.  `tempN_input` is mandatory for a sensor to be reported at
+// all; `tempN_label`/`tempN_max`/`tempN_crit` are all optional and a missing one simply leaves
+// the matching field at a fallback label or `None`.
+fn build_readings(chip_name: &str, files: &HashMap<String, String>) -> Vec<SensorReading> {
+    // Sort on the parsed integer, not the string: `indices.sort()` on `Vec<String>` would put
+    // "10" before "2" lexicographically, scrambling the order on any chip (eg `coretemp` on a
+    // many-core CPU) with 10 or more `tempN_input` files.
+    let mut indices: Vec<(u32, String)> = files
+        .keys()
+        .filter_map(|name| temp_index_from_filename(name))
+        .filter_map(|n| Some((n.parse::<u32>().ok()?, n)))
+        .collect();
+    indices.sort();
+    indices.dedup();
+    let indices: Vec<String> = indices.into_iter().map(|(_, n)| n).collect();
+
+    let mut readings = vec![];
+    for n in indices {
+        let Some(temp_c) = files
+            .get(&format!("temp{n}_input"))
+            .and_then(|s| parse_millidegrees(s))
+        else {
+            continue;
+        };
+        let label = files
+            .get(&format!("temp{n}_label"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_else(|| format!("temp{n}"));
+        let max_c = files
+            .get(&format!("temp{n}_max"))
+            .and_then(|s| parse_millidegrees(s));
+        let crit_c = files
+            .get(&format!("temp{n}_crit"))
+            .and_then(|s| parse_millidegrees(s));
+        readings.push(SensorReading {
+            chip_name: chip_name.to_string(),
+            label,
+            temp_c,
+            max_c,
+            crit_c,
+        });
+    }
+    readings
+}
+
+// Extracts "3" from "temp3_input"; anything else (the `_label`/`_max`/`_crit` siblings, or a
+// non-numeric or malformed name) is not an index on its own and is picked up instead by the
+// `format!("temp{n}_...")` lookups in `build_readings`.
+fn temp_index_from_filename(name: &str) -> Option<String> {
+    let n = name.strip_prefix("temp")?.strip_suffix("_input")?;
+    if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) {
+        Some(n.to_string())
+    } else {
+        None
+    }
+}
+
+fn parse_millidegrees(text: &str) -> Option<f64> {
+    let millidegrees = text.trim().parse::<f64>().ok()?;
+    Some(millidegrees / 1000.0)
+}
+
+#[test]
+fn test_parse_millidegrees() {
+    assert_eq!(parse_millidegrees("45000\n"), Some(45.0));
+}
+
+#[test]
+fn test_parse_millidegrees_garbage() {
+    assert_eq!(parse_millidegrees("nonsense\n"), None);
+}
+
+#[test]
+fn test_temp_index_from_filename() {
+    assert_eq!(temp_index_from_filename("temp1_input"), Some("1".to_string()));
+    assert_eq!(temp_index_from_filename("temp12_input"), Some("12".to_string()));
+    assert_eq!(temp_index_from_filename("temp1_label"), None);
+    assert_eq!(temp_index_from_filename("in0_input"), None);
+}
+
+#[test]
+fn test_build_readings_basic() {
+    let mut files = HashMap::new();
+    files.insert("temp1_input".to_string(), "45000\n".to_string());
+    files.insert("temp1_label".to_string(), "Package id 0\n".to_string());
+    files.insert("temp1_max".to_string(), "90000\n".to_string());
+    files.insert("temp1_crit".to_string(), "100000\n".to_string());
+    let readings = build_readings("coretemp", &files);
+    assert_eq!(
+        readings,
+        vec![SensorReading {
+            chip_name: "coretemp".to_string(),
+            label: "Package id 0".to_string(),
+            temp_c: 45.0,
+            max_c: Some(90.0),
+            crit_c: Some(100.0),
+        }]
+    );
+}
+
+#[test]
+fn test_build_readings_tolerates_missing_optional_files() {
+    let mut files = HashMap::new();
+    files.insert("temp1_input".to_string(), "50000\n".to_string());
+    let readings = build_readings("nvme", &files);
+    assert_eq!(readings.len(), 1);
+    assert_eq!(readings[0].label, "temp1");
+    assert_eq!(readings[0].max_c, None);
+    assert_eq!(readings[0].crit_c, None);
+}
+
+#[test]
+fn test_build_readings_multiple_sensors_sorted_and_deduped() {
+    let mut files = HashMap::new();
+    files.insert("temp2_input".to_string(), "60000\n".to_string());
+    files.insert("temp1_input".to_string(), "40000\n".to_string());
+    files.insert("temp1_max".to_string(), "85000\n".to_string());
+    let readings = build_readings("coretemp", &files);
+    assert_eq!(readings.len(), 2);
+    assert_eq!(readings[0].temp_c, 40.0);
+    assert_eq!(readings[1].temp_c, 60.0);
+}
+
+#[test]
+fn test_build_readings_sorts_double_digit_indices_numerically() {
+    // A lexicographic sort on the index strings would order these "1, 10, 2" instead of "1, 2,
+    // 10", as happens on eg `coretemp` with 10 or more cores.
+    let mut files = HashMap::new();
+    files.insert("temp10_input".to_string(), "30000\n".to_string());
+    files.insert("temp2_input".to_string(), "20000\n".to_string());
+    files.insert("temp1_input".to_string(), "10000\n".to_string());
+    let readings = build_readings("coretemp", &files);
+    assert_eq!(readings.len(), 3);
+    assert_eq!(readings[0].label, "temp1");
+    assert_eq!(readings[1].label, "temp2");
+    assert_eq!(readings[2].label, "temp10");
+}
+
+#[test]
+fn test_build_readings_skips_sensor_missing_input() {
+    let mut files = HashMap::new();
+    files.insert("temp1_label".to_string(), "orphaned label\n".to_string());
+    let readings = build_readings("coretemp", &files);
+    assert!(readings.is_empty());
+}
@@ -0,0 +1,242 @@
+/// Collect network-interface and protocol counters from /proc/net/dev and /proc/net/snmp, a
+/// sibling of `procfs::get_memory`/`procfs::get_cpu_info` for nodes where operators want NIC
+/// saturation or UDP packet-loss visibility alongside CPU/memory/process data - useful on
+/// HPC/data-center hosts where a single job hammering the network can starve everyone else on the
+/// same fabric.
+
+#[cfg(test)]
+use crate::mocksystem;
+use crate::procfsapi;
+#[cfg(test)]
+use crate::systemapi::SystemAPI;
+
+use std::collections::HashMap;
+
+/// Cumulative since-boot counters for one network interface, as reported by the `Receive`/
+/// `Transmit` columns of /proc/net/dev.
+#[derive(PartialEq, Debug)]
+pub struct InterfaceCounters {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errs: u64,
+    pub rx_drop: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errs: u64,
+    pub tx_drop: u64,
+}
+
+/// Aggregate protocol counters from /proc/net/snmp.  Fields absent from that file (eg an older
+/// kernel missing `RcvbufErrors`/`SndbufErrors`) are simply zero rather than failing the whole
+/// read, since the rest of the data is still useful.
+#[derive(PartialEq, Debug, Default)]
+pub struct ProtocolCounters {
+    pub udp_in_datagrams: u64,
+    pub udp_out_datagrams: u64,
+    pub udp_in_errors: u64,
+    pub udp_rcvbuf_errors: u64,
+    pub udp_sndbuf_errors: u64,
+    pub tcp_retrans_segs: u64,
+}
+
+pub struct NetworkInfo {
+    pub interfaces: Vec<InterfaceCounters>,
+    // Sum of `interfaces`, excluding the loopback interface ("lo"), which never leaves the host
+    // and would otherwise hide real NIC saturation behind harmless intra-host traffic.
+    pub totals: InterfaceCounters,
+    pub protocol: ProtocolCounters,
+}
+
+pub fn get_network_info(fs: &dyn procfsapi::ProcfsAPI) -> Result<NetworkInfo, String> {
+    let interfaces = parse_net_dev(&fs.read_to_string("net/dev")?)?;
+    let totals = sum_interfaces(&interfaces);
+    let protocol = parse_net_snmp(&fs.read_to_string("net/snmp")?)?;
+    Ok(NetworkInfo { interfaces, totals, protocol })
+}
+
+// /proc/net/dev has two header lines followed by one line per interface, of the form
+// "  eth0: <8 receive fields> <8 transmit fields>"; the receive/transmit columns are (in order)
+// bytes packets errs drop fifo frame compressed multicast / bytes packets errs drop fifo colls
+// carrier compressed.  We only surface the first four of each half.
+fn parse_net_dev(text: &str) -> Result<Vec<InterfaceCounters>, String> {
+    let mut interfaces = vec![];
+    for l in text.lines() {
+        let Some((name, rest)) = l.split_once(':') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = rest.split_ascii_whitespace().collect();
+        if fields.len() < 16 {
+            continue;
+        }
+        let field = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
+        interfaces.push(InterfaceCounters {
+            name: name.to_string(),
+            rx_bytes: field(0),
+            rx_packets: field(1),
+            rx_errs: field(2),
+            rx_drop: field(3),
+            tx_bytes: field(8),
+            tx_packets: field(9),
+            tx_errs: field(10),
+            tx_drop: field(11),
+        });
+    }
+    if interfaces.is_empty() {
+        return Err(format!("No interfaces found in /proc/net/dev: {text}"));
+    }
+    Ok(interfaces)
+}
+
+fn sum_interfaces(interfaces: &[InterfaceCounters]) -> InterfaceCounters {
+    let mut totals = InterfaceCounters {
+        name: "total".to_string(),
+        rx_bytes: 0,
+        rx_packets: 0,
+        rx_errs: 0,
+        rx_drop: 0,
+        tx_bytes: 0,
+        tx_packets: 0,
+        tx_errs: 0,
+        tx_drop: 0,
+    };
+    for iface in interfaces {
+        if iface.name == "lo" {
+            continue;
+        }
+        totals.rx_bytes += iface.rx_bytes;
+        totals.rx_packets += iface.rx_packets;
+        totals.rx_errs += iface.rx_errs;
+        totals.rx_drop += iface.rx_drop;
+        totals.tx_bytes += iface.tx_bytes;
+        totals.tx_packets += iface.tx_packets;
+        totals.tx_errs += iface.tx_errs;
+        totals.tx_drop += iface.tx_drop;
+    }
+    totals
+}
+
+// /proc/net/snmp is a sequence of header/value line pairs, each pair introduced by the same
+// "Proto:" tag, eg:
+//   Udp: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors
+//   Udp: 1234 0 0 5678 1 2
+// We key every field by (protocol, field name) so the caller doesn't have to know each protocol's
+// column order, which does vary across kernel versions.
+fn parse_net_snmp(text: &str) -> Result<ProtocolCounters, String> {
+    let mut fields: HashMap<(String, String), u64> = HashMap::new();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+    while i + 1 < lines.len() {
+        let Some((proto_h, names)) = lines[i].split_once(':') else {
+            i += 1;
+            continue;
+        };
+        let Some((proto_v, values)) = lines[i + 1].split_once(':') else {
+            i += 1;
+            continue;
+        };
+        if proto_h != proto_v {
+            i += 1;
+            continue;
+        }
+        let proto = proto_h.trim().to_string();
+        let names: Vec<&str> = names.split_ascii_whitespace().collect();
+        let values: Vec<&str> = values.split_ascii_whitespace().collect();
+        for (name, value) in names.iter().zip(values.iter()) {
+            if let Ok(n) = value.parse::<u64>() {
+                fields.insert((proto.clone(), name.to_string()), n);
+            }
+        }
+        i += 2;
+    }
+    let get = |proto: &str, name: &str| {
+        fields
+            .get(&(proto.to_string(), name.to_string()))
+            .copied()
+            .unwrap_or(0)
+    };
+    Ok(ProtocolCounters {
+        udp_in_datagrams: get("Udp", "InDatagrams"),
+        udp_out_datagrams: get("Udp", "OutDatagrams"),
+        udp_in_errors: get("Udp", "InErrors"),
+        udp_rcvbuf_errors: get("Udp", "RcvbufErrors"),
+        udp_sndbuf_errors: get("Udp", "SndbufErrors"),
+        tcp_retrans_segs: get("Tcp", "RetransSegs"),
+    })
+}
+
+#[cfg(test)]
+fn net_dev_text() -> String {
+    "Inter-|   Receive                                                |  Transmit\n \
+     face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n\
+        lo:  1000      10    0    0    0     0          0         0     1000      10    0    0    0     0       0          0\n\
+      eth0: 50000     100    1    2    0     0          0         0   20000      80    3    4    0     0       0          0\n"
+        .to_string()
+}
+
+#[cfg(test)]
+fn net_snmp_text() -> String {
+    "Ip: Forwarding DefaultTTL\nIp: 1 64\n\
+     Tcp: RtoAlgorithm RtoMin RtoMax MaxConn RetransSegs\nTcp: 1 200 120000 -1 42\n\
+     Udp: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors\nUdp: 900 0 3 800 5 6\n"
+        .to_string()
+}
+
+#[test]
+fn test_parse_net_dev_basic() {
+    let interfaces = parse_net_dev(&net_dev_text()).expect("Test: Must have data");
+    assert_eq!(interfaces.len(), 2);
+    assert_eq!(interfaces[1].name, "eth0");
+    assert_eq!(interfaces[1].rx_bytes, 50000);
+    assert_eq!(interfaces[1].rx_errs, 1);
+    assert_eq!(interfaces[1].tx_bytes, 20000);
+    assert_eq!(interfaces[1].tx_errs, 3);
+}
+
+#[test]
+fn test_parse_net_dev_empty_is_error() {
+    assert!(parse_net_dev("Inter-|   Receive\n face |bytes\n").is_err());
+}
+
+#[test]
+fn test_sum_interfaces_excludes_loopback() {
+    let interfaces = parse_net_dev(&net_dev_text()).expect("Test: Must have data");
+    let totals = sum_interfaces(&interfaces);
+    assert_eq!(totals.rx_bytes, 50000);
+    assert_eq!(totals.tx_bytes, 20000);
+}
+
+#[test]
+fn test_parse_net_snmp_basic() {
+    let protocol = parse_net_snmp(&net_snmp_text()).expect("Test: Must have data");
+    assert_eq!(protocol.udp_in_datagrams, 900);
+    assert_eq!(protocol.udp_out_datagrams, 800);
+    assert_eq!(protocol.udp_in_errors, 3);
+    assert_eq!(protocol.udp_rcvbuf_errors, 5);
+    assert_eq!(protocol.udp_sndbuf_errors, 6);
+    assert_eq!(protocol.tcp_retrans_segs, 42);
+}
+
+#[test]
+fn test_parse_net_snmp_missing_fields_default_zero() {
+    let text = "Udp: InDatagrams\nUdp: 5\n".to_string();
+    let protocol = parse_net_snmp(&text).expect("Test: Must have data");
+    assert_eq!(protocol.udp_in_datagrams, 5);
+    assert_eq!(protocol.tcp_retrans_segs, 0);
+}
+
+#[test]
+fn test_get_network_info() {
+    let mut files = HashMap::new();
+    files.insert("net/dev".to_string(), net_dev_text());
+    files.insert("net/snmp".to_string(), net_snmp_text());
+    let system = mocksystem::MockSystem::new().with_files(files).freeze();
+    let info = get_network_info(system.get_procfs()).expect("Test: Must have data");
+    assert_eq!(info.interfaces.len(), 2);
+    assert_eq!(info.totals.rx_bytes, 50000);
+    assert_eq!(info.protocol.udp_in_datagrams, 900);
+}
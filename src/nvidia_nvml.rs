@@ -1,44 +1,702 @@
 // Could use bindgen but not important now
 
+use crate::gpu;
+use crate::ps::UserTable;
+
+#[repr(C)]
+pub struct NvmlProcessInfo {
+    pub pid: cty::uint32_t,
+    pub used_gpu_memory: cty::uint64_t,
+}
+
+// Mirrors nvml.h's `nvmlValue_t` union: a sample's value is one of these three representations,
+// selected by the `value_type` tag `nvml_device_get_samples` reports alongside it; see
+// `decode_sample`.
+#[repr(C)]
+pub union NvmlValue {
+    pub d_val: cty::c_double,
+    pub ui_val: cty::uint32_t,
+    pub ul_val: cty::uint64_t,
+}
+
+// Mirrors nvml.h's `nvmlSample_t`.
+#[repr(C)]
+pub struct NvmlSample {
+    pub timestamp_us: cty::uint64_t,
+    pub value: NvmlValue,
+}
+
 extern "C" {
     pub fn nvml_open() -> cty::c_int;
     pub fn nvml_close() -> cty::c_int;
     pub fn nvml_device_get_count(count: *mut cty::uint32_t) -> cty::c_int;
     pub fn nvml_device_get_architecture(device: cty::uint32_t, arch: *mut cty::uint32_t) -> cty::c_int;
     pub fn nvml_device_get_memory_info(device: cty::uint32_t, total: *mut cty::uint64_t, used: *mut cty::uint64_t, free: *mut cty::uint64_t) -> cty::c_int;
+    // Mirrors `nvmlDeviceGetComputeRunningProcesses_v3`/`nvmlDeviceGetGraphicsRunningProcesses`:
+    // fills in up to `*count` entries of `infos` with one {pid, usedGpuMemory} record per process
+    // currently using `device`'s compute or graphics engines, and writes the true number of
+    // running processes back into `*count`.  As with the underlying NVML call, passing a `*count`
+    // smaller than the true number (in particular 0, with `infos` null) is how you're expected to
+    // probe the required size: the shim returns `NVML_ERROR_INSUFFICIENT_SIZE` and still updates
+    // `*count`, see `device_processes` below.
+    pub fn nvml_device_get_processes(
+        device: cty::uint32_t,
+        infos: *mut NvmlProcessInfo,
+        count: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // nvmlDeviceGetUtilizationRates: percent of the last sampling period the device's compute
+    // (`gpu_pct`) and memory controller (`mem_pct`) were busy - the number operators actually want
+    // to answer "is this reservation being driven?".
+    pub fn nvml_device_get_utilization_rates(
+        device: cty::uint32_t,
+        gpu_pct: *mut cty::uint32_t,
+        mem_pct: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // nvmlDeviceGetPowerUsage: current draw, in milliwatts.
+    pub fn nvml_device_get_power_usage(
+        device: cty::uint32_t,
+        milliwatts: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // nvmlDeviceGetTemperature: takes an `nvmlTemperatureSensors_t`; we only ever ask for the die
+    // sensor (`NVML_TEMPERATURE_GPU`, see below), in degrees Celsius.
+    pub fn nvml_device_get_temperature(
+        device: cty::uint32_t,
+        sensor_type: cty::uint32_t,
+        celsius: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // nvmlDeviceGetClockInfo: takes an `nvmlClockType_t`; we only ever ask for the graphics clock
+    // (`NVML_CLOCK_GRAPHICS`, see below), in MHz.
+    pub fn nvml_device_get_clock_info(
+        device: cty::uint32_t,
+        clock_type: cty::uint32_t,
+        mhz: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // nvmlErrorString: the human-readable description NVML itself has on file for a given
+    // nvmlReturn_t code, eg "Insufficient Size".  Used by `check` below to flesh out `NvmlError`
+    // without us having to hand-maintain a message for every code.
+    pub fn nvml_error_string(code: cty::c_int) -> *const cty::c_char;
+    // nvmlDeviceGetPciInfo: domain/bus/device locate the card in the PCI topology (and so, via
+    // sysfs, its NUMA node); `pci_device_id` packs vendor and device id into one `u32` exactly as
+    // nvml.h's `nvmlPciInfo_t.pciDeviceId` does (device id in the high 16 bits, vendor id in the
+    // low 16).  `bus_id` is a caller-allocated, NUL-terminated buffer of at least
+    // `NVML_DEVICE_PCI_BUS_ID_BUFFER_SIZE` bytes, eg "00000000:01:00.0".
+    pub fn nvml_device_get_pci_info(
+        device: cty::uint32_t,
+        bus_id: *mut cty::c_char,
+        bus_id_len: cty::uint32_t,
+        domain: *mut cty::uint32_t,
+        bus: *mut cty::uint32_t,
+        dev: *mut cty::uint32_t,
+        pci_device_id: *mut cty::uint32_t,
+    ) -> cty::c_int;
+    // nvmlDeviceGetUUID: a globally unique identifier for this physical card (eg
+    // "GPU-xxxxxxxx-xxxx-...") that survives reboots and NVML index reassignment, unlike the
+    // `device` index itself.  `uuid` is a caller-allocated, NUL-terminated buffer of at least
+    // `NVML_DEVICE_UUID_BUFFER_SIZE` bytes.
+    pub fn nvml_device_get_uuid(
+        device: cty::uint32_t,
+        uuid: *mut cty::c_char,
+        uuid_len: cty::uint32_t,
+    ) -> cty::c_int;
+    // nvmlDeviceGetSamples: fetch the batch of `{timestamp, value}` readings the driver has
+    // accumulated for `device` of kind `sampling_type` since `last_seen_timestamp_us`.  Follows the
+    // same null-buffer size-probe convention as `nvml_device_get_processes`: call once with
+    // `samples` null to learn `*value_type`/`*count`, allocate a buffer of that size, call again to
+    // fill it in.  `*value_type` tells us which field of each sample's `NvmlValue` union is live,
+    // see `decode_sample` below.
+    pub fn nvml_device_get_samples(
+        device: cty::uint32_t,
+        sampling_type: cty::uint32_t,
+        last_seen_timestamp_us: cty::uint64_t,
+        value_type: *mut cty::uint32_t,
+        count: *mut cty::uint32_t,
+        samples: *mut NvmlSample,
+    ) -> cty::c_int;
+}
+
+// From nvml.h's nvmlReturn_t enum.
+const NVML_ERROR_UNINITIALIZED: cty::c_int = 1;
+const NVML_ERROR_NOT_SUPPORTED: cty::c_int = 3;
+const NVML_ERROR_NO_PERMISSION: cty::c_int = 4;
+const NVML_ERROR_INSUFFICIENT_SIZE: cty::c_int = 7;
+
+// From nvml.h's nvmlTemperatureSensors_t and nvmlClockType_t enums.
+const NVML_TEMPERATURE_GPU: cty::uint32_t = 0;
+const NVML_CLOCK_GRAPHICS: cty::uint32_t = 0;
+
+// From nvml.h: minimum caller-allocated buffer sizes for `nvmlDeviceGetPciInfo`'s `busId` and
+// `nvmlDeviceGetUUID`'s `uuid` out-parameters.
+const NVML_DEVICE_PCI_BUS_ID_BUFFER_SIZE: usize = 32;
+const NVML_DEVICE_UUID_BUFFER_SIZE: usize = 80;
+
+// From nvml.h's nvmlSamplingType_t enum - the subset we collect.
+const NVML_TOTAL_POWER_SAMPLES: cty::uint32_t = 0;
+const NVML_GPU_UTILIZATION_SAMPLES: cty::uint32_t = 1;
+const NVML_MEMORY_UTILIZATION_SAMPLES: cty::uint32_t = 2;
+
+// From nvml.h's nvmlValueType_t enum: which field of `NvmlValue` a sample's `value` actually holds.
+const NVML_VALUE_TYPE_DOUBLE: cty::uint32_t = 0;
+const NVML_VALUE_TYPE_UNSIGNED_INT: cty::uint32_t = 1;
+const NVML_VALUE_TYPE_UNSIGNED_LONG: cty::uint32_t = 2;
+const NVML_VALUE_TYPE_UNSIGNED_LONG_LONG: cty::uint32_t = 3;
+
+// However churny the GPU's process list is, we have to give up probing it eventually rather than
+// spin forever; five rounds of growing the buffer to the size NVML itself just reported is already
+// generous.
+const MAX_SIZE_PROBE_ATTEMPTS: usize = 5;
+
+/// Which broad category an NVML failure falls into - the distinction a monitoring daemon actually
+/// needs in order to decide whether to log-and-skip, back off, or alert.  `Unknown` covers every
+/// `nvmlReturn_t` code we haven't bothered to name individually.
+#[derive(Debug, PartialEq, Eq)]
+pub enum NvmlErrorKind {
+    Uninitialized,
+    NotSupported,
+    NoPermission,
+    InsufficientSize,
+    Unknown,
+}
+
+/// A failed NVML call, carrying both the raw `nvmlReturn_t` code and the description NVML itself
+/// gives for it via `nvmlErrorString`.
+#[derive(Debug)]
+pub struct NvmlError {
+    pub kind: NvmlErrorKind,
+    pub code: cty::c_int,
+    pub message: String,
+}
+
+impl std::fmt::Display for NvmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "NVML error {} ({:?}): {}",
+            self.code, self.kind, self.message
+        )
+    }
+}
+
+impl std::error::Error for NvmlError {}
+
+fn nvml_error_message(code: cty::c_int) -> String {
+    let ptr = unsafe { nvml_error_string(code) };
+    if ptr.is_null() {
+        return format!("unknown NVML error {code}");
+    }
+    unsafe { std::ffi::CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Read a NUL-terminated string out of a caller-allocated, NVML-filled `c_char` buffer, eg the
+/// `bus_id`/`uuid` out-parameters of `nvml_device_get_pci_info`/`nvml_device_get_uuid`.
+fn c_buf_to_string(buf: &[cty::c_char]) -> String {
+    unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Mirrors nvml-wrapper's `nvml_try`: turn a raw `nvmlReturn_t` into `Ok(())` for success or a
+/// typed `NvmlError` otherwise, so that callers can use `?` instead of hand-rolled `!= 0` checks
+/// that discard the actual failure reason.
+fn check(code: cty::c_int) -> Result<(), NvmlError> {
+    if code == 0 {
+        return Ok(());
+    }
+    let kind = match code {
+        NVML_ERROR_UNINITIALIZED => NvmlErrorKind::Uninitialized,
+        NVML_ERROR_NOT_SUPPORTED => NvmlErrorKind::NotSupported,
+        NVML_ERROR_NO_PERMISSION => NvmlErrorKind::NoPermission,
+        NVML_ERROR_INSUFFICIENT_SIZE => NvmlErrorKind::InsufficientSize,
+        _ => NvmlErrorKind::Unknown,
+    };
+    Err(NvmlError {
+        kind,
+        code,
+        message: nvml_error_message(code),
+    })
+}
+
+/// A card's stable identity, as opposed to its NVML `device` index, which is only a position in
+/// the current enumeration order and can reorder across reboots or driver reloads.  `uuid` is the
+/// identifier to key on across sampling runs; `bus_id`/`domain`/`bus`/`device` locate it in the
+/// PCI topology (and so, via sysfs, its NUMA node) for placement analysis.
+pub struct DeviceIdentity {
+    pub bus_id: String,
+    pub domain: u32,
+    pub bus: u32,
+    pub device: u32,
+    // `nvmlPciInfo_t.pciDeviceId`: device id in the high 16 bits, vendor id in the low 16.
+    pub pci_device_id: u32,
+    pub uuid: String,
+}
+
+/// Fetch the stable identity of `device` (an NVML index, as from `nvml_device_get_count`).
+pub fn get_device_identity(device: cty::uint32_t) -> Result<DeviceIdentity, NvmlError> {
+    let mut bus_id_buf = [0 as cty::c_char; NVML_DEVICE_PCI_BUS_ID_BUFFER_SIZE];
+    let mut domain: cty::uint32_t = 0;
+    let mut bus: cty::uint32_t = 0;
+    let mut dev: cty::uint32_t = 0;
+    let mut pci_device_id: cty::uint32_t = 0;
+    check(unsafe {
+        nvml_device_get_pci_info(
+            device,
+            bus_id_buf.as_mut_ptr(),
+            bus_id_buf.len() as cty::uint32_t,
+            &mut domain,
+            &mut bus,
+            &mut dev,
+            &mut pci_device_id,
+        )
+    })?;
+
+    let mut uuid_buf = [0 as cty::c_char; NVML_DEVICE_UUID_BUFFER_SIZE];
+    check(unsafe {
+        nvml_device_get_uuid(
+            device,
+            uuid_buf.as_mut_ptr(),
+            uuid_buf.len() as cty::uint32_t,
+        )
+    })?;
+
+    Ok(DeviceIdentity {
+        bus_id: c_buf_to_string(&bus_id_buf),
+        domain,
+        bus,
+        device: dev,
+        pci_device_id,
+        uuid: c_buf_to_string(&uuid_buf),
+    })
+}
+
+/// Which of NVML's accumulated time-series buffers to read via `nvml_device_get_samples`.
+#[derive(Clone, Copy)]
+pub enum SamplingType {
+    GpuUtilization,
+    MemoryUtilization,
+    Power,
+}
+
+impl SamplingType {
+    fn nvml_code(self) -> cty::uint32_t {
+        match self {
+            SamplingType::GpuUtilization => NVML_GPU_UTILIZATION_SAMPLES,
+            SamplingType::MemoryUtilization => NVML_MEMORY_UTILIZATION_SAMPLES,
+            SamplingType::Power => NVML_TOTAL_POWER_SAMPLES,
+        }
+    }
+}
+
+/// One reading from a `nvml_device_get_samples` buffer, decoded to `f64` regardless of which
+/// `NvmlValue` field the driver actually reported it in.
+pub struct Sample {
+    pub timestamp_us: u64,
+    pub value: f64,
+}
+
+fn decode_sample(raw: &NvmlSample, value_type: cty::uint32_t) -> Sample {
+    let value = match value_type {
+        NVML_VALUE_TYPE_DOUBLE => unsafe { raw.value.d_val },
+        NVML_VALUE_TYPE_UNSIGNED_INT => unsafe { raw.value.ui_val as f64 },
+        NVML_VALUE_TYPE_UNSIGNED_LONG | NVML_VALUE_TYPE_UNSIGNED_LONG_LONG => unsafe {
+            raw.value.ul_val as f64
+        },
+        _ => 0.0,
+    };
+    Sample {
+        timestamp_us: raw.timestamp_us,
+        value,
+    }
+}
+
+/// Fetch every sample of `sampling_type` NVML has accumulated for `device` since
+/// `last_seen_timestamp_us` (pass 0 on a device's first call), handling the same null-buffer
+/// size-probe convention as `device_processes`, including its `NVML_ERROR_INSUFFICIENT_SIZE` retry
+/// loop for a buffer that grows between the two calls.  Returns the decoded samples and the
+/// highest timestamp among them; any failure, or no new samples since `last_seen_timestamp_us`
+/// (which NVML reports as an error rather than an empty buffer), is treated as "nothing new" and
+/// returns `last_seen_timestamp_us` unchanged so the next collection doesn't skip ahead.
+fn device_samples(
+    device: cty::uint32_t,
+    sampling_type: cty::uint32_t,
+    last_seen_timestamp_us: u64,
+) -> (Vec<Sample>, u64) {
+    let mut value_type: cty::uint32_t = 0;
+    let mut count: cty::uint32_t = 0;
+    for _ in 0..MAX_SIZE_PROBE_ATTEMPTS {
+        let rc = unsafe {
+            nvml_device_get_samples(
+                device,
+                sampling_type,
+                last_seen_timestamp_us,
+                &mut value_type,
+                &mut count,
+                std::ptr::null_mut(),
+            )
+        };
+        if count == 0 {
+            return (vec![], last_seen_timestamp_us);
+        }
+        if rc != 0 && rc != NVML_ERROR_INSUFFICIENT_SIZE {
+            return (vec![], last_seen_timestamp_us);
+        }
+        let mut raw = (0..count)
+            .map(|_| NvmlSample {
+                timestamp_us: 0,
+                value: NvmlValue { ul_val: 0 },
+            })
+            .collect::<Vec<NvmlSample>>();
+        let rc = unsafe {
+            nvml_device_get_samples(
+                device,
+                sampling_type,
+                last_seen_timestamp_us,
+                &mut value_type,
+                &mut count,
+                raw.as_mut_ptr(),
+            )
+        };
+        if rc == 0 {
+            raw.truncate(count as usize);
+            let samples = raw
+                .iter()
+                .map(|s| decode_sample(s, value_type))
+                .collect::<Vec<Sample>>();
+            let highest = samples
+                .iter()
+                .map(|s| s.timestamp_us)
+                .max()
+                .unwrap_or(last_seen_timestamp_us);
+            return (samples, highest);
+        }
+        if rc != NVML_ERROR_INSUFFICIENT_SIZE {
+            return (vec![], last_seen_timestamp_us);
+        }
+        // `count` grew between the two calls above; loop around and probe again with the new size.
+    }
+    (vec![], last_seen_timestamp_us)
+}
+
+/// Peak and average of a batch of samples - what sonar reports for the interval between collection
+/// ticks instead of (or alongside) a single instantaneous reading.
+pub struct SampleStats {
+    pub peak: f64,
+    pub average: f64,
+}
+
+fn sample_stats(samples: &[Sample]) -> Option<SampleStats> {
+    if samples.is_empty() {
+        return None;
+    }
+    let peak = samples.iter().map(|s| s.value).fold(f64::MIN, f64::max);
+    let average = samples.iter().map(|s| s.value).sum::<f64>() / samples.len() as f64;
+    Some(SampleStats { peak, average })
 }
 
-pub fn experiment() {
+/// Fetch `sampling_type`'s peak and average since `last_seen_timestamp_us` for `device` (pass 0 on
+/// a device's first call), and the new cursor value to pass in on the next call - the same
+/// previous/current state-threading convention as `interval_cpu_pct`/`cpu_ticks_snapshot` in
+/// procfs.rs.  Returns `None` alongside an unchanged cursor if no new samples are available,
+/// including if this sampling type isn't supported on this card or driver version.
+pub fn get_gpu_sample_stats(
+    device: cty::uint32_t,
+    sampling_type: SamplingType,
+    last_seen_timestamp_us: u64,
+) -> (Option<SampleStats>, u64) {
+    let (samples, highest) =
+        device_samples(device, sampling_type.nvml_code(), last_seen_timestamp_us);
+    (sample_stats(&samples), highest)
+}
+
+/// Query the processes currently running on `device`, handling NVML's two-call size-probe
+/// convention: call once with an empty buffer to learn how many processes there are, allocate a
+/// buffer of that size, and call again to fill it in.  A process can start using the device in
+/// between the two calls, in which case the second call can also come back with
+/// `NVML_ERROR_INSUFFICIENT_SIZE` and a larger `count`; retry with the new size, up to
+/// `MAX_SIZE_PROBE_ATTEMPTS` times, rather than looping forever on a GPU that's constantly
+/// churning through short-lived jobs.  Any other failure is treated as "no processes" - the device
+/// may simply not support process enumeration.
+fn device_processes(device: cty::uint32_t) -> Vec<NvmlProcessInfo> {
+    let mut count: cty::uint32_t = 0;
+    for _ in 0..MAX_SIZE_PROBE_ATTEMPTS {
+        let rc = unsafe { nvml_device_get_processes(device, std::ptr::null_mut(), &mut count) };
+        if count == 0 {
+            return vec![];
+        }
+        if rc != 0 && rc != NVML_ERROR_INSUFFICIENT_SIZE {
+            return vec![];
+        }
+        let mut infos = (0..count)
+            .map(|_| NvmlProcessInfo {
+                pid: 0,
+                used_gpu_memory: 0,
+            })
+            .collect::<Vec<NvmlProcessInfo>>();
+        let rc = unsafe { nvml_device_get_processes(device, infos.as_mut_ptr(), &mut count) };
+        if rc == 0 {
+            infos.truncate(count as usize);
+            return infos;
+        }
+        if rc != NVML_ERROR_INSUFFICIENT_SIZE {
+            return vec![];
+        }
+        // `count` grew between the two calls above; loop around and probe again with the new size.
+    }
+    vec![]
+}
+
+/// Query every device (`ndev` is the count from `nvml_device_get_count`) and join the resulting
+/// per-device process lists against sonar's `user_by_pid` table, producing one `gpu::Process`
+/// record per (device, pid) pair carrying that process's GPU memory footprint.  SM/memory
+/// utilization percentages aren't available from this NVML call, so `gpu_pct`/`mem_pct` are left
+/// at zero here.
+pub fn get_process_utilization(ndev: cty::uint32_t, user_by_pid: &UserTable) -> Vec<gpu::Process> {
+    let per_device_info = (0..ndev)
+        .map(|dev| (dev as usize, device_processes(dev)))
+        .collect::<Vec<(usize, Vec<NvmlProcessInfo>)>>();
+    extract_nvidia_process_information(&per_device_info, user_by_pid)
+}
+
+fn extract_nvidia_process_information(
+    per_device_info: &[(usize, Vec<NvmlProcessInfo>)],
+    user_by_pid: &UserTable,
+) -> Vec<gpu::Process> {
+    let mut processes = vec![];
+    for (device, infos) in per_device_info {
+        for info in infos {
+            let pid = info.pid as usize;
+            let (user, uid) = if let Some((user, uid)) = user_by_pid.get(&pid) {
+                (user.to_string(), *uid)
+            } else {
+                ("_zombie_".to_owned() + &pid.to_string(), gpu::ZOMBIE_UID)
+            };
+            processes.push(gpu::Process {
+                device: Some(*device),
+                pid,
+                user,
+                uid,
+                gpu_pct: 0.0,
+                mem_pct: 0.0,
+                mem_size_kib: (info.used_gpu_memory / 1024) as usize,
+                command: "_noinfo_".to_string(),
+            });
+        }
+    }
+    processes
+}
+
+/// A per-device snapshot of whether a GPU reservation is actually being driven: SM and
+/// memory-controller utilization, power draw, die temperature, and graphics clock, all as of one
+/// sampling tick.  Meant to be emitted alongside the CPU/memory metrics `process`/`sysinfo`
+/// collect, so that an idle-looking but allocated GPU shows up as idle rather than just absent.
+pub struct GpuSample {
+    pub device: usize,
+    pub gpu_utilization_pct: f64,
+    pub memory_utilization_pct: f64,
+    pub power_usage_milliwatts: u64,
+    pub temperature_celsius: u64,
+    pub clock_mhz: u64,
+}
+
+/// Sample every device (`ndev` is the count from `nvml_device_get_count`).  A device that fails
+/// the (load-bearing) utilization-rates query is omitted rather than reported with zeroes, since
+/// that would misleadingly read as "confirmed idle" rather than "couldn't tell"; the other three
+/// queries are individually best-effort and simply read back as 0 if unsupported on a given card
+/// (eg older cards without a power sensor).
+pub fn get_gpu_samples(ndev: cty::uint32_t) -> Vec<GpuSample> {
+    (0..ndev).filter_map(get_gpu_sample).collect()
+}
+
+fn get_gpu_sample(device: cty::uint32_t) -> Option<GpuSample> {
+    let mut gpu_pct: cty::uint32_t = 0;
+    let mut mem_pct: cty::uint32_t = 0;
+    if unsafe { nvml_device_get_utilization_rates(device, &mut gpu_pct, &mut mem_pct) } != 0 {
+        return None;
+    }
+
+    let mut milliwatts: cty::uint32_t = 0;
+    if unsafe { nvml_device_get_power_usage(device, &mut milliwatts) } != 0 {
+        milliwatts = 0;
+    }
+
+    let mut celsius: cty::uint32_t = 0;
+    if unsafe { nvml_device_get_temperature(device, NVML_TEMPERATURE_GPU, &mut celsius) } != 0 {
+        celsius = 0;
+    }
+
+    let mut mhz: cty::uint32_t = 0;
+    if unsafe { nvml_device_get_clock_info(device, NVML_CLOCK_GRAPHICS, &mut mhz) } != 0 {
+        mhz = 0;
+    }
+
+    Some(GpuSample {
+        device: device as usize,
+        gpu_utilization_pct: gpu_pct as f64,
+        memory_utilization_pct: mem_pct as f64,
+        power_usage_milliwatts: milliwatts as u64,
+        temperature_celsius: celsius as u64,
+        clock_mhz: mhz as u64,
+    })
+}
+
+pub fn experiment() -> Result<(), NvmlError> {
     println!("Experiment");
     unsafe {
-        if nvml_open() != 0 {
-            println!("nvml_open failed\n");
-            return
-        }
+        check(nvml_open())?;
 
         let mut ndev: cty::uint32_t = 0;
-        if nvml_device_get_count(&mut ndev) != 0 {
-            println!("nvml_device_get_count returned 0\n");
-            return
-        }
+        check(nvml_device_get_count(&mut ndev))?;
         println!("devices: {ndev}");
 
         for i in 0..ndev {
             let mut arch: cty::uint32_t = 0;
-            if nvml_device_get_architecture(i, &mut arch) != 0 {
-                continue
-            }
+            check(nvml_device_get_architecture(i, &mut arch))?;
             println!("device_get_architecture {i} {arch}");
 
             let mut total: cty::uint64_t = 0;
             let mut used: cty::uint64_t = 0;
             let mut free: cty::uint64_t = 0;
-            if nvml_device_get_memory_info(i, &mut total, &mut used, &mut free) != 0 {
-                continue
-            }
+            check(nvml_device_get_memory_info(
+                i, &mut total, &mut used, &mut free,
+            ))?;
             println!("device_get_memory_info {i} {total} {used} {free}");
+
+            for info in device_processes(i) {
+                println!(
+                    "device_get_processes {i} pid={} used_gpu_memory={}",
+                    info.pid, info.used_gpu_memory
+                );
+            }
+
+            if let Some(sample) = get_gpu_sample(i) {
+                println!(
+                    "device_get_utilization_rates/power_usage/temperature/clock_info {i} gpu%={} mem%={} mW={} C={} MHz={}",
+                    sample.gpu_utilization_pct,
+                    sample.memory_utilization_pct,
+                    sample.power_usage_milliwatts,
+                    sample.temperature_celsius,
+                    sample.clock_mhz
+                );
+            }
+
+            let (stats, _) = get_gpu_sample_stats(i, SamplingType::GpuUtilization, 0);
+            if let Some(stats) = stats {
+                println!(
+                    "device_get_samples {i} gpu_utilization peak={} average={}",
+                    stats.peak, stats.average
+                );
+            }
+        }
+
+        check(nvml_close())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::map;
+
+    #[test]
+    fn test_check_maps_zero_to_ok() {
+        assert!(check(0).is_ok());
+    }
+
+    #[test]
+    fn test_decode_sample_by_value_type() {
+        let d = NvmlSample {
+            timestamp_us: 100,
+            value: NvmlValue { d_val: 12.5 },
+        };
+        assert_eq!(decode_sample(&d, NVML_VALUE_TYPE_DOUBLE).value, 12.5);
+
+        let ui = NvmlSample {
+            timestamp_us: 200,
+            value: NvmlValue { ui_val: 42 },
+        };
+        assert_eq!(decode_sample(&ui, NVML_VALUE_TYPE_UNSIGNED_INT).value, 42.0);
+
+        let ul = NvmlSample {
+            timestamp_us: 300,
+            value: NvmlValue { ul_val: 9000 },
+        };
+        assert_eq!(
+            decode_sample(&ul, NVML_VALUE_TYPE_UNSIGNED_LONG).value,
+            9000.0
+        );
+        assert_eq!(
+            decode_sample(&ul, NVML_VALUE_TYPE_UNSIGNED_LONG_LONG).value,
+            9000.0
+        );
+    }
+
+    #[test]
+    fn test_sample_stats_peak_and_average() {
+        let samples = vec![
+            Sample {
+                timestamp_us: 1,
+                value: 10.0,
+            },
+            Sample {
+                timestamp_us: 2,
+                value: 30.0,
+            },
+            Sample {
+                timestamp_us: 3,
+                value: 20.0,
+            },
+        ];
+        let stats = sample_stats(&samples).unwrap();
+        assert_eq!(stats.peak, 30.0);
+        assert_eq!(stats.average, 20.0);
+    }
+
+    #[test]
+    fn test_sample_stats_empty_is_none() {
+        assert!(sample_stats(&[]).is_none());
+    }
+
+    #[test]
+    fn test_c_buf_to_string_stops_at_nul() {
+        let mut buf = [0 as cty::c_char; 16];
+        for (i, b) in b"GPU-1234\0garbage".iter().enumerate() {
+            buf[i] = *b as cty::c_char;
         }
+        assert_eq!(c_buf_to_string(&buf), "GPU-1234");
+    }
 
-        nvml_close();
+    #[test]
+    fn test_extract_nvidia_process_information() {
+        let users = map! {
+            1234 => ("alice", 1001usize)
+        };
+        let per_device_info = vec![
+            (
+                0,
+                vec![
+                    NvmlProcessInfo {
+                        pid: 1234,
+                        used_gpu_memory: 2 * 1024 * 1024,
+                    },
+                    NvmlProcessInfo {
+                        pid: 9999, // not in `users`, ie already exited
+                        used_gpu_memory: 1024,
+                    },
+                ],
+            ),
+            (1, vec![]),
+        ];
+        let processes = extract_nvidia_process_information(&per_device_info, &users);
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[0].device, Some(0));
+        assert_eq!(processes[0].pid, 1234);
+        assert_eq!(processes[0].user, "alice");
+        assert_eq!(processes[0].uid, 1001);
+        assert_eq!(processes[0].mem_size_kib, 2 * 1024);
+        assert_eq!(processes[1].device, Some(0));
+        assert_eq!(processes[1].pid, 9999);
+        assert_eq!(processes[1].user, "_zombie_9999");
+        assert_eq!(processes[1].uid, gpu::ZOMBIE_UID);
     }
-}
\ No newline at end of file
+}
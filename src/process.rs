@@ -1,8 +1,8 @@
 /// Collect CPU process information without GPU information.
-
 use crate::command::{self, CmdError};
 use crate::procfs;
 use crate::util;
+use std::collections::{HashMap, HashSet};
 
 #[derive(PartialEq)]
 pub struct Process {
@@ -13,9 +13,14 @@ pub struct Process {
     pub mem_pct: f64,
     pub cputime_sec: usize,
     pub mem_size_kib: usize,
+    pub rss_kib: usize,
     pub command: String,
+    // Full argv from /proc/{pid}/cmdline, space-joined; falls back to the (possibly truncated)
+    // `command` field when cmdline is empty, eg for kernel threads like `kworker/0:0H`.
+    pub full_command: String,
     pub ppid: usize,
     pub session: usize,
+    pub state: char,
 }
 
 /// Obtain process information and return a vector of structures with all the information we need.
@@ -43,31 +48,55 @@ const TIMEOUT_SECONDS: u64 = 2; // for `ps`
 //
 // The format of `bsdtime` is `m...m:ss` in minutes and seconds.
 
+// `rss` gives us the resident set size in KiB directly, alongside `size` (the text+data estimate
+// ps calls "size"), so that consumers can tell real physical footprint from address-space size.
+
 const PS_COMMAND: &str =
-    "ps -e --no-header --cumulative -o pid,uid,user:22,pcpu,pmem,bsdtime,size,ppid,sess,comm";
+    "ps -e --no-header --cumulative -o pid,uid,user:22,pcpu,pmem,bsdtime,size,rss,ppid,sess,stat,comm";
 
 fn parse_ps_output(raw_text: &str) -> Vec<Process> {
     raw_text
         .lines()
         .map(|line| {
             let (start_indices, parts) = util::chunks(line);
+            let pid = parts[0].parse::<usize>().unwrap();
+            // this is done because command can have spaces
+            let command = line[start_indices[11]..].to_string();
+            let full_command = full_command_for(pid, &command);
             Process {
-                pid: parts[0].parse::<usize>().unwrap(),
+                pid,
                 uid: parts[1].parse::<usize>().unwrap(),
                 user: parts[2].to_string(),
                 cpu_pct: parts[3].parse::<f64>().unwrap(),
                 mem_pct: parts[4].parse::<f64>().unwrap(),
                 cputime_sec: parse_bsdtime(parts[5]),
                 mem_size_kib: parts[6].parse::<usize>().unwrap(),
-                ppid: parts[7].to_string().parse::<usize>().unwrap(),
-                session: parts[8].to_string().parse::<usize>().unwrap(),
-                // this is done because command can have spaces
-                command: line[start_indices[9]..].to_string(),
+                rss_kib: parts[7].parse::<usize>().unwrap(),
+                ppid: parts[8].to_string().parse::<usize>().unwrap(),
+                session: parts[9].to_string().parse::<usize>().unwrap(),
+                // `stat` can be multiple characters (eg `Ss`, `R+`); the leading one is the
+                // run state proper, the rest are modifier flags we don't currently care about.
+                state: parts[10].chars().next().unwrap_or('?'),
+                command,
+                full_command,
             }
         })
         .collect::<Vec<Process>>()
 }
 
+// /proc/{pid}/cmdline holds the untruncated argv, NUL-separated and NUL-terminated; `ps`'s `comm`
+// column truncates to 15 characters, which makes eg every Firefox content process show up as
+// "Isolated Web Co".  A kernel thread (or a process that's exited since `ps` ran) has no cmdline,
+// in which case we fall back to `fallback_command` (the `comm` value).
+fn full_command_for(pid: usize, fallback_command: &str) -> String {
+    match std::fs::read_to_string(format!("/proc/{pid}/cmdline")) {
+        Ok(cmdline) if !cmdline.trim_matches('\0').is_empty() => {
+            cmdline.trim_end_matches('\0').replace('\0', " ")
+        }
+        _ => fallback_command.to_string(),
+    }
+}
+
 fn parse_bsdtime<'a>(s: &'a str) -> usize {
     let ss = s.split(':').collect::<Vec<&'a str>>();
     if ss.len() != 2 {
@@ -77,15 +106,270 @@ fn parse_bsdtime<'a>(s: &'a str) -> usize {
     }
 }
 
+/// A resource rollup for one subtree of the process forest (or one session): the root's `pid`
+/// together with the summed `cputime_sec`, `mem_size_kib`, `cpu_pct`, and `mem_pct` of the root
+/// and everything beneath it.
+pub struct ProcessTreeNode {
+    pub pid: usize,
+    pub cputime_sec: usize,
+    pub mem_size_kib: usize,
+    pub cpu_pct: f64,
+    pub mem_pct: f64,
+}
+
+impl ProcessTreeNode {
+    fn new(pid: usize) -> ProcessTreeNode {
+        ProcessTreeNode {
+            pid,
+            cputime_sec: 0,
+            mem_size_kib: 0,
+            cpu_pct: 0.0,
+            mem_pct: 0.0,
+        }
+    }
+
+    fn add(&mut self, p: &Process) {
+        self.cputime_sec += p.cputime_sec;
+        self.mem_size_kib += p.mem_size_kib;
+        self.cpu_pct += p.cpu_pct;
+        self.mem_pct += p.mem_pct;
+    }
+}
+
+/// Reconstruct the `ps auxfw`-style process forest from a flat process list (using `ppid`) and
+/// roll up resource usage along it: each returned `ProcessTreeNode` is a root of the forest,
+/// accumulating the `cputime_sec`, `mem_size_kib`, `cpu_pct`, and `mem_pct` of itself and every
+/// descendant.  The raw per-pid `processes` passed in are untouched; this only adds the
+/// aggregated view on top.
+///
+/// A process whose `ppid` doesn't name another process in `processes` (like #80199 in the test
+/// data below) is reparented to pid 1 rather than dropped, so its subtree still
+/// shows up, rolled into pid 1's.
+pub fn rollup_process_tree(processes: &[Process]) -> Vec<ProcessTreeNode> {
+    rollup(processes, |p| p.ppid)
+}
+
+/// As `rollup_process_tree`, but groups by `session` instead of `ppid`, so that e.g. all the
+/// workers forked by one login shell are reported as a single record keyed on the session
+/// leader's pid, regardless of how they're scattered across the `ppid` forest.
+pub fn rollup_process_sessions(processes: &[Process]) -> Vec<ProcessTreeNode> {
+    rollup(processes, |p| p.session)
+}
+
+fn rollup(processes: &[Process], parent_of: impl Fn(&Process) -> usize) -> Vec<ProcessTreeNode> {
+    let by_pid: HashMap<usize, &Process> = processes.iter().map(|p| (p.pid, p)).collect();
+
+    // A process that names itself as its own parent (pid 1's `ppid` field is conventionally 0,
+    // which is never a pid we observe, so this is also how pid 1 ends up a root) is a root
+    // outright.  Otherwise, if the parent isn't present in `processes` at all, it's reparented to
+    // pid 1 rather than dropped (see #80199 in the test data below).  Everything else becomes a child
+    // of its (possibly reparented) parent.
+    let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut roots = vec![];
+    for p in processes {
+        let parent = parent_of(p);
+        if parent == p.pid {
+            roots.push(p.pid);
+            continue;
+        }
+        let parent = if by_pid.contains_key(&parent) {
+            parent
+        } else {
+            1
+        };
+        if parent == p.pid {
+            roots.push(p.pid);
+        } else {
+            children.entry(parent).or_default().push(p.pid);
+        }
+    }
+
+    // Guard against cycles: a pid is only ever summed into the first subtree that reaches it
+    // during the DFS.  Any pid that's still unvisited once every declared root has been walked is
+    // part of a cycle with no natural root (ppid / session pointing at each other in a loop); emit
+    // it as a root of its own rather than silently dropping it.
+    let mut visited = HashSet::new();
+    let rollup_from = |root: usize, visited: &mut HashSet<usize>| {
+        let mut node = ProcessTreeNode::new(root);
+        let mut stack = vec![root];
+        while let Some(pid) = stack.pop() {
+            if !visited.insert(pid) {
+                continue;
+            }
+            if let Some(p) = by_pid.get(&pid) {
+                node.add(p);
+            }
+            if let Some(kids) = children.get(&pid) {
+                stack.extend(kids.iter().copied());
+            }
+        }
+        node
+    };
+
+    let mut result: Vec<ProcessTreeNode> = roots
+        .into_iter()
+        .map(|root| rollup_from(root, &mut visited))
+        .collect();
+    for p in processes {
+        if !visited.contains(&p.pid) {
+            result.push(rollup_from(p.pid, &mut visited));
+        }
+    }
+    result
+}
+
+/// A synthesized record summarizing one folded subtree: the root process's `pid` and `command`,
+/// together with the summed `cpu_pct`, `mem_pct`, and `rss_kib` of the root and every descendant,
+/// and a `child_count` of how many processes were folded into it (not counting the root itself).
+pub struct FoldedProcess {
+    pub pid: usize,
+    pub command: String,
+    pub cpu_pct: f64,
+    pub mem_pct: f64,
+    pub rss_kib: usize,
+    pub child_count: usize,
+}
+
+/// Walk `processes`' `ppid` forest and fold every descendant of a process whose `command` is in
+/// `aggregating_commands` into a single `FoldedProcess` record keyed on that root, summing
+/// `cpu_pct`, `mem_pct`, and `rss_kib` and counting how many processes were folded in.  This turns
+/// eg Firefox's ~50 "Isolated Web Co"/"Web Content"/"RDD Process" children into one "firefox"
+/// record instead of hundreds of indistinguishable ones.
+///
+/// Processes that aren't descendants of an aggregating root - including kernel threads, whose
+/// `ppid` is conventionally 2 and so are never reachable from a userspace root - are returned
+/// unchanged, as single-process records with a `child_count` of 0.
+///
+/// A process whose `ppid` doesn't name another process in `processes` is treated as a root of its
+/// own rather than joined to anything (unlike `rollup` above, which reparents it to pid 1).
+/// `visited` also guards against cycles, since it prevents a pid from being folded into more than
+/// one group.
+///
+/// An aggregating-command process that is itself a descendant of another aggregating-command
+/// process (eg a "Web Content" child that happens to have been relaunched as another "firefox"
+/// process) is not a root of its own group: it's folded into its ancestor's, same as any other
+/// descendant.  Roots are therefore determined structurally up front, mirroring `rollup`'s
+/// classify-then-DFS shape, rather than by "first unvisited match in iteration order" - the
+/// latter would make the result depend on whether a descendant happens to appear before its own
+/// ancestor in `processes`.
+pub fn fold_aggregating_commands(
+    processes: &[Process],
+    aggregating_commands: &HashSet<&str>,
+) -> Vec<FoldedProcess> {
+    let by_pid: HashMap<usize, &Process> = processes.iter().map(|p| (p.pid, p)).collect();
+    let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+    for p in processes {
+        if p.ppid != p.pid && by_pid.contains_key(&p.ppid) {
+            children.entry(p.ppid).or_default().push(p.pid);
+        }
+    }
+
+    // A pid is dominated (ie not a root) if walking up its `ppid` chain reaches another process
+    // whose command is in `aggregating_commands` before running off the end of the chain.  Memoized
+    // since the same ancestor chain can be walked again from a sibling; `on_stack` breaks cycles by
+    // treating a pid revisited mid-walk as having no aggregating ancestor, the same "no natural
+    // root" call `rollup` makes for a ppid/session cycle.
+    let mut dominated_memo: HashMap<usize, bool> = HashMap::new();
+    let mut on_stack: HashSet<usize> = HashSet::new();
+    fn is_dominated<'a>(
+        pid: usize,
+        by_pid: &HashMap<usize, &'a Process>,
+        aggregating_commands: &HashSet<&str>,
+        memo: &mut HashMap<usize, bool>,
+        on_stack: &mut HashSet<usize>,
+    ) -> bool {
+        if let Some(&result) = memo.get(&pid) {
+            return result;
+        }
+        if !on_stack.insert(pid) {
+            return false;
+        }
+        let result = match by_pid.get(&pid) {
+            Some(p) if p.ppid != p.pid => match by_pid.get(&p.ppid) {
+                Some(parent) if aggregating_commands.contains(parent.command.as_str()) => true,
+                Some(_) => is_dominated(p.ppid, by_pid, aggregating_commands, memo, on_stack),
+                None => false,
+            },
+            _ => false,
+        };
+        on_stack.remove(&pid);
+        memo.insert(pid, result);
+        result
+    }
+
+    let mut visited = HashSet::new();
+    let mut result = vec![];
+
+    // Fold the descendants of every aggregating root first, so that eg a "firefox" aggregating
+    // root claims its subtree before any of those pids are considered individually below.
+    for p in processes {
+        if visited.contains(&p.pid) || !aggregating_commands.contains(p.command.as_str()) {
+            continue;
+        }
+        if is_dominated(
+            p.pid,
+            &by_pid,
+            aggregating_commands,
+            &mut dominated_memo,
+            &mut on_stack,
+        ) {
+            continue;
+        }
+        let mut folded = FoldedProcess {
+            pid: p.pid,
+            command: p.command.clone(),
+            cpu_pct: 0.0,
+            mem_pct: 0.0,
+            rss_kib: 0,
+            child_count: 0,
+        };
+        let mut stack = vec![p.pid];
+        while let Some(pid) = stack.pop() {
+            if !visited.insert(pid) {
+                continue;
+            }
+            if let Some(q) = by_pid.get(&pid) {
+                folded.cpu_pct += q.cpu_pct;
+                folded.mem_pct += q.mem_pct;
+                folded.rss_kib += q.rss_kib;
+                if pid != p.pid {
+                    folded.child_count += 1;
+                }
+            }
+            if let Some(kids) = children.get(&pid) {
+                stack.extend(kids.iter().copied());
+            }
+        }
+        result.push(folded);
+    }
+
+    // Anything left over wasn't reachable from an aggregating root, so it's emitted unchanged.
+    for p in processes {
+        if visited.insert(p.pid) {
+            result.push(FoldedProcess {
+                pid: p.pid,
+                command: p.command.clone(),
+                cpu_pct: p.cpu_pct,
+                mem_pct: p.mem_pct,
+                rss_kib: p.rss_kib,
+                child_count: 0,
+            });
+        }
+    }
+
+    result
+}
+
 #[cfg(test)]
 pub fn parsed_test_output() -> Vec<Process> {
-    let text = "   2022 1001 bob                            10.0 20.0 1:28 553348 1234 0 slack
-  42178 1001 bob                            10.0 15.0 1:29 353348 1235 1 chromium
-  42178 1001 bob                            10.0 15.0 1:30 5536  1236 2 chromium
-  42189 1002 alice                          10.0  5.0 1:31 5528  1237 3 slack
-  42191 1001 bob                            10.0  5.0 1:32 5552  1238 4 someapp
-  42213 1002 alice                          10.0  5.0 1:33 348904 1239 5 some app
-  42213 1002 alice                          10.0  5.0 1:34 135364 1240 6 some app";
+    let text =
+        "   2022 1001 bob                            10.0 20.0 1:28 553348 400000 1234 0 S slack
+  42178 1001 bob                            10.0 15.0 1:29 353348 300000 1235 1 R chromium
+  42178 1001 bob                            10.0 15.0 1:30 5536  4000  1236 2 S chromium
+  42189 1002 alice                          10.0  5.0 1:31 5528  4000  1237 3 S slack
+  42191 1001 bob                            10.0  5.0 1:32 5552  4000  1238 4 D someapp
+  42213 1002 alice                          10.0  5.0 1:33 348904 300000 1239 5 Z some app
+  42213 1002 alice                          10.0  5.0 1:34 135364 100000 1240 6 S some app";
 
     parse_ps_output(text)
 }
@@ -93,7 +377,7 @@ pub fn parsed_test_output() -> Vec<Process> {
 #[test]
 fn test_parse_ps_output() {
     macro_rules! proc(
-        { $a:expr, $b:expr, $c:expr, $d:expr, $e: expr, $f:expr, $g:expr, $h:expr, $i:expr, $j:expr } => {
+        { $a:expr, $b:expr, $c:expr, $d:expr, $e: expr, $f:expr, $g:expr, $h:expr, $r:expr, $i:expr, $k:expr, $j:expr } => {
             Process { pid: $a,
                       uid: $b,
                       user: $c.to_string(),
@@ -102,19 +386,22 @@ fn test_parse_ps_output() {
                       cputime_sec: $f,
                       ppid: $g,
                       mem_size_kib: $h,
+                      rss_kib: $r,
                       session: $i,
+                      state: $k,
                       command: $j.to_string(),
+                      full_command: $j.to_string(),
             }
         });
 
     assert!(parsed_test_output().into_iter().eq(vec![
-        proc! {  2022, 1001, "bob",   10.0, 20.0, 60+28, 1234, 553348, 0, "slack" },
-        proc! { 42178, 1001, "bob",   10.0, 15.0, 60+29, 1235, 353348, 1, "chromium" },
-        proc! { 42178, 1001, "bob",   10.0, 15.0, 60+30, 1236,   5536, 2, "chromium" },
-        proc! { 42189, 1002, "alice", 10.0,  5.0, 60+31, 1237,  5528, 3, "slack" },
-        proc! { 42191, 1001, "bob",   10.0,  5.0, 60+32, 1238,  5552, 4, "someapp" },
-        proc! { 42213, 1002, "alice", 10.0,  5.0, 60+33, 1239, 348904, 5, "some app" },
-        proc! { 42213, 1002, "alice", 10.0,  5.0, 60+34, 1240, 135364, 6, "some app" }
+        proc! {  2022, 1001, "bob",   10.0, 20.0, 60+28, 1234, 553348, 400000, 0, 'S', "slack" },
+        proc! { 42178, 1001, "bob",   10.0, 15.0, 60+29, 1235, 353348, 300000, 1, 'R', "chromium" },
+        proc! { 42178, 1001, "bob",   10.0, 15.0, 60+30, 1236,   5536,   4000, 2, 'S', "chromium" },
+        proc! { 42189, 1002, "alice", 10.0,  5.0, 60+31, 1237,  5528,   4000, 3, 'S', "slack" },
+        proc! { 42191, 1001, "bob",   10.0,  5.0, 60+32, 1238,  5552,   4000, 4, 'D', "someapp" },
+        proc! { 42213, 1002, "alice", 10.0,  5.0, 60+33, 1239, 348904, 300000, 5, 'Z', "some app" },
+        proc! { 42213, 1002, "alice", 10.0,  5.0, 60+34, 1240, 135364, 100000, 6, 'S', "some app" }
     ]))
 }
 
@@ -123,330 +410,450 @@ pub fn parsed_full_test_output() -> Vec<Process> {
     // Generated by PS_COMMAND_COMPLETE on lth's laptop, slightly edited to orphan #80199
     //"ps -e --no-header -o pid,user:22,pcpu,pmem,size,ppid,sess,comm"
     // Subsequently added synthetic cputimes number
-    // pid user                pcpu pmem  cputimes size     ppid    sess command
-    let text =
-"      1 0 root                    0.0  0.0 1:28 21516       0       1 systemd
-      2 0 root                    0.0  0.0     1:28 0       0       0 kthreadd
-      3 0 root                    0.0  0.0     1:28 0       2       0 rcu_gp
-      4 0 root                    0.0  0.0     1:28 0       2       0 rcu_par_gp
-      5 0 root                    0.0  0.0     1:28 0       2       0 slub_flushwq
-      6 0 root                    0.0  0.0     1:28 0       2       0 netns
-      8 0 root                    0.0  0.0     1:28 0       2       0 kworker/0:0H-events_highpri
-     10 0 root                    0.0  0.0     1:28 0       2       0 mm_percpu_wq
-     11 0 root                    0.0  0.0     1:28 0       2       0 rcu_tasks_kthread
-     12 0 root                    0.0  0.0     1:28 0       2       0 rcu_tasks_rude_kthread
-     13 0 root                    0.0  0.0     1:28 0       2       0 rcu_tasks_trace_kthread
-     14 0 root                    0.0  0.0     1:28 0       2       0 ksoftirqd/0
-     15 0 root                    0.0  0.0     1:28 0       2       0 rcu_preempt
-     16 0 root                    0.0  0.0     1:28 0       2       0 migration/0
-     17 0 root                    0.0  0.0     1:28 0       2       0 idle_inject/0
-     19 0 root                    0.0  0.0     1:28 0       2       0 cpuhp/0
-     20 0 root                    0.0  0.0     1:28 0       2       0 cpuhp/1
-     21 0 root                    0.0  0.0     1:28 0       2       0 idle_inject/1
-     22 0 root                    0.0  0.0     1:28 0       2       0 migration/1
-     23 0 root                    0.0  0.0     1:28 0       2       0 ksoftirqd/1
-     25 0 root                    0.0  0.0     1:28 0       2       0 kworker/1:0H-events_highpri
-     26 0 root                    0.0  0.0     1:28 0       2       0 cpuhp/2
-     27 0 root                    0.0  0.0     1:28 0       2       0 idle_inject/2
-     28 0 root                    0.0  0.0     1:28 0       2       0 migration/2
-     29 0 root                    0.0  0.0     1:28 0       2       0 ksoftirqd/2
-     31 0 root                    0.0  0.0     1:28 0       2       0 kworker/2:0H-events_highpri
-     32 0 root                    0.0  0.0     1:28 0       2       0 cpuhp/3
-     33 0 root                    0.0  0.0     1:28 0       2       0 idle_inject/3
-     34 0 root                    0.0  0.0     1:28 0       2       0 migration/3
-     35 0 root                    0.0  0.0     1:28 0       2       0 ksoftirqd/3
-     37 0 root                    0.0  0.0     1:28 0       2       0 kworker/3:0H-events_highpri
-     38 0 root                    0.0  0.0     1:28 0       2       0 cpuhp/4
-     39 0 root                    0.0  0.0     1:28 0       2       0 idle_inject/4
-     40 0 root                    0.0  0.0     1:28 0       2       0 migration/4
-     41 0 root                    0.0  0.0     1:28 0       2       0 ksoftirqd/4
-     43 0 root                    0.0  0.0     1:28 0       2       0 kworker/4:0H-kblockd
-     44 0 root                    0.0  0.0     1:28 0       2       0 cpuhp/5
-     45 0 root                    0.0  0.0     1:28 0       2       0 idle_inject/5
-     46 0 root                    0.0  0.0     1:28 0       2       0 migration/5
-     47 0 root                    0.0  0.0     1:28 0       2       0 ksoftirqd/5
-     49 0 root                    0.0  0.0     1:28 0       2       0 kworker/5:0H-events_highpri
-     50 0 root                    0.0  0.0     1:28 0       2       0 cpuhp/6
-     51 0 root                    0.0  0.0     1:28 0       2       0 idle_inject/6
-     52 0 root                    0.0  0.0     1:28 0       2       0 migration/6
-     53 0 root                    0.0  0.0     1:28 0       2       0 ksoftirqd/6
-     55 0 root                    0.0  0.0     1:28 0       2       0 kworker/6:0H-events_highpri
-     56 0 root                    0.0  0.0     1:28 0       2       0 cpuhp/7
-     57 0 root                    0.0  0.0     1:28 0       2       0 idle_inject/7
-     58 0 root                    0.0  0.0     1:28 0       2       0 migration/7
-     59 0 root                    0.0  0.0     1:28 0       2       0 ksoftirqd/7
-     61 0 root                    0.0  0.0     1:28 0       2       0 kworker/7:0H-events_highpri
-     62 0 root                    0.0  0.0     1:28 0       2       0 kdevtmpfs
-     63 0 root                    0.0  0.0     1:28 0       2       0 inet_frag_wq
-     64 0 root                    0.0  0.0     1:28 0       2       0 kauditd
-     65 0 root                    0.0  0.0     1:28 0       2       0 khungtaskd
-     67 0 root                    0.0  0.0     1:28 0       2       0 oom_reaper
-     69 0 root                    0.0  0.0     1:28 0       2       0 writeback
-     70 0 root                    0.0  0.0     1:28 0       2       0 kcompactd0
-     71 0 root                    0.0  0.0     1:28 0       2       0 ksmd
-     72 0 root                    0.0  0.0     1:28 0       2       0 khugepaged
-     73 0 root                    0.0  0.0     1:28 0       2       0 kintegrityd
-     74 0 root                    0.0  0.0     1:28 0       2       0 kblockd
-     75 0 root                    0.0  0.0     1:28 0       2       0 blkcg_punt_bio
-     78 0 root                    0.0  0.0     1:28 0       2       0 tpm_dev_wq
-     79 0 root                    0.0  0.0     1:28 0       2       0 ata_sff
-     81 0 root                    0.0  0.0     1:28 0       2       0 md
-     82 0 root                    0.0  0.0     1:28 0       2       0 edac-poller
-     83 0 root                    0.0  0.0     1:28 0       2       0 devfreq_wq
-     84 0 root                    0.0  0.0     1:28 0       2       0 watchdogd
-     85 0 root                    0.0  0.0     1:28 0       2       0 kworker/0:1H-acpi_thermal_pm
-     86 0 root                    0.0  0.0     1:28 0       2       0 kswapd0
-     87 0 root                    0.0  0.0     1:28 0       2       0 ecryptfs-kthread
-     93 0 root                    0.0  0.0     1:28 0       2       0 kthrotld
-     98 0 root                    0.0  0.0     1:28 0       2       0 irq/124-pciehp
-     99 0 root                    0.0  0.0     1:28 0       2       0 irq/125-pciehp
-    104 0 root                    0.0  0.0     1:28 0       2       0 acpi_thermal_pm
-    105 0 root                    0.0  0.0     1:28 0       2       0 xenbus_probe
-    107 0 root                    0.0  0.0     1:28 0       2       0 vfio-irqfd-clea
-    108 0 root                    0.0  0.0     1:28 0       2       0 mld
-    109 0 root                    0.0  0.0     1:28 0       2       0 kworker/5:1H-kblockd
-    110 0 root                    0.0  0.0     1:28 0       2       0 ipv6_addrconf
-    115 0 root                    0.0  0.0     1:28 0       2       0 kstrp
-    121 0 root                    0.0  0.0     1:28 0       2       0 zswap-shrink
-    170 0 root                    0.0  0.0     1:28 0       2       0 charger_manager
-    208 0 root                    0.0  0.0     1:28 0       2       0 kworker/7:1H-events_highpri
-    229 0 root                    0.0  0.0     1:28 0       2       0 kworker/3:1H-events_highpri
-    231 0 root                    0.0  0.0     1:28 0       2       0 nvme-wq
-    232 0 root                    0.0  0.0     1:28 0       2       0 nvme-reset-wq
-    233 0 root                    0.0  0.0     1:28 0       2       0 nvme-delete-wq
-    238 0 root                    0.0  0.0     1:28 0       2       0 irq/173-SYNA30B7:00
-    239 0 root                    0.0  0.0     1:28 0       2       0 kworker/2:1H-events_highpri
-    243 0 root                    0.0  0.0     1:28 0       2       0 irq/174-WACF4233:00
-    267 0 root                    0.0  0.0     1:28 0       2       0 jbd2/nvme0n1p2-8
-    268 0 root                    0.0  0.0     1:28 0       2       0 ext4-rsv-conver
-    303 0 root                    0.0  0.0     1:28 0       2       0 kworker/6:1H-kblockd
-    308 0 root                    0.0  0.3 1:28 18052       1     308 systemd-journal
-    335 0 root                    0.0  0.0     1:28 0       2       0 kworker/4:1H-events_highpri
-    336 0 root                    0.0  0.0     1:28 0       2       0 kworker/1:1H-events_highpri
-    339 0 root                    0.0  0.0  1:28 2676       1     339 systemd-udevd
-    469 0 root                    0.0  0.0     1:28 0       2       0 cfg80211
-    485 0 root                    0.0  0.0     1:28 0       2       0 irq/175-iwlwifi:default_queue
-    488 0 root                    0.0  0.0     1:28 0       2       0 irq/176-iwlwifi:queue_1
-    489 0 root                    0.0  0.0     1:28 0       2       0 irq/177-iwlwifi:queue_2
-    490 0 root                    0.0  0.0     1:28 0       2       0 irq/178-iwlwifi:queue_3
-    491 0 root                    0.0  0.0     1:28 0       2       0 irq/179-iwlwifi:queue_4
-    492 0 root                    0.0  0.0     1:28 0       2       0 irq/180-iwlwifi:queue_5
-    493 0 root                    0.0  0.0     1:28 0       2       0 irq/181-iwlwifi:queue_6
-    494 0 root                    0.0  0.0     1:28 0       2       0 irq/182-iwlwifi:queue_7
-    496 0 root                    0.0  0.0     1:28 0       2       0 irq/183-iwlwifi:queue_8
-    498 0 root                    0.0  0.0     1:28 0       2       0 irq/184-iwlwifi:exception
-    512 1 systemd-oom             0.0  0.0 1:33   740       1     512 systemd-oomd
-    513 2 systemd-resolve         0.0  0.0 1:33  5204       1     513 systemd-resolve
-    514 3 systemd-timesync        0.0  0.0 1:33  8944       1     514 systemd-timesyn
-    535 0 root                    0.0  0.0 1:33     0       2       0 cryptd
-    581 0 root                    0.0  0.0 1:33 25828       1     581 accounts-daemon
-    584 0 root                    0.0  0.0 1:33   360       1     584 acpid
-    587 4 avahi                   0.0  0.0 1:33   636       1     587 avahi-daemon
-    589 0 root                    0.0  0.0 1:33   440       1     589 cron
-    590 5 messagebus              0.0  0.0 1:33  3512       1     590 dbus-daemon
-    592 0 root                    0.0  0.1 1:33 28332       1     592 NetworkManager
-    602 0 root                    0.0  0.0 1:33  8916       1     602 irqbalance
-    616 0 root                    0.0  0.1 1:33 10896       1     616 networkd-dispat
-    617 0 root                    0.0  0.0 1:33 28820       1     617 polkitd
-    618 0 root                    0.0  0.0 1:33 25796       1     618 power-profiles-
-    619 6 syslog                  0.0  0.0 1:33 18708       1     619 rsyslogd
-    621 0 root                    0.0  0.2 1:33 263568      1     621 snapd
-    626 0 root                    0.0  0.0 1:33 25828       1     626 switcheroo-cont
-    643 0 root                    0.0  0.0 1:33 33780       1     643 systemd-logind
-    654 0 root                    0.0  0.0 1:33 25984       1     654 thermald
-    655 0 root                    0.0  0.0 1:33 43880       1     655 udisksd
-    677 0 root                    0.0  0.0 1:33  2020       1     677 wpa_supplicant
-    687 4 avahi                   0.0  0.0 1:33   448     587     587 avahi-daemon
-    719 0 root                    0.0  0.0 1:33 34868       1     719 ModemManager
-    722 0 root                    0.0  0.0 1:33 25764       1     722 boltd
-    751 0 root                    0.0  0.1 1:33 18004       1     751 unattended-upgr
-    757 0 root                    0.0  0.0 1:33 26100       1     757 gdm3
-    761 0 root                    0.0  0.0 1:33 32580       1     761 iio-sensor-prox
-    792 0 root                    0.0  0.0 1:33   584       1     792 bluetoothd
-    799 0 root                    0.0  0.0 1:33     0       2       0 card0-crtc0
-    800 0 root                    0.0  0.0 1:33     0       2       0 card0-crtc1
-    801 0 root                    0.0  0.0 1:33     0       2       0 card0-crtc2
-    802 0 root                    0.0  0.0 1:33     0       2       0 card0-crtc3
-    960 0 root                    0.0  0.0 1:33     0       2       0 irq/207-AudioDSP
-   1079 7 rtkit                   0.0  0.0 1:33 17076       1    1079 rtkit-daemon
-   1088 0 root                    0.0  0.0 1:33 26144       1    1088 upowerd
-   1352 0 root                    0.0  0.2 1:33 50776       1    1352 packagekitd
-   1523 8 colord                  0.0  0.0 1:33 28708       1    1523 colord
-   1618 9 kernoops                0.0  0.0 1:33   520       1    1618 kerneloops
-   1622 9 kernoops                0.0  0.0 1:33   520       1    1622 kerneloops
-   1789 0 root                    0.0  0.0 1:33 35428     757     757 gdm-session-wor
-   1804 1001 larstha                 0.0  0.0 1:33  2216       1    1804 systemd
-   1805 1001 larstha                 0.0  0.0 1:33 20556    1804    1804 (sd-pam)
-   1811 1001 larstha                 0.0  0.0 1:33 25636    1804    1811 pipewire
-   1812 1001 larstha                 0.0  0.0 1:33  9256    1804    1812 pipewire-media-
-   1813 1001 larstha                 0.1  0.1 1:33 72012    1804    1813 pulseaudio
-   1823 1001 larstha                 0.0  0.0 1:33  2624    1804    1823 dbus-daemon
-   1825 1001 larstha                 0.0  0.0 1:33 59244       1    1824 gnome-keyring-d
-   1834 1001 larstha                 0.0  0.0 1:33 25792    1804    1834 gvfsd
-   1840 1001 larstha                 0.0  0.0 1:33 44420    1804    1834 gvfsd-fuse
-   1855 1001 larstha                 0.0  0.0 1:33 60976    1804    1855 xdg-document-po
-   1859 1001 larstha                 0.0  0.0 1:33 25536    1804    1859 xdg-permission-
-   1865 0 root                    0.0  0.0 1:33   356    1855    1865 fusermount3
-   1884 1001 larstha                 0.0  0.1 1:33 151232   1804    1884 tracker-miner-f
-   1892 0 root                    0.0  0.0 1:33     0       2       0 krfcommd
-   1894 1001 larstha                 0.0  0.0 1:33 35316    1804    1894 gvfs-udisks2-vo
-   1899 1001 larstha                 0.0  0.0 1:33 25708    1804    1899 gvfs-mtp-volume
-   1903 1001 larstha                 0.0  0.0 1:33 25688    1804    1903 gvfs-goa-volume
-   1907 1001 larstha                 0.0  0.2 1:33 44544    1804    1823 goa-daemon
-   1914 1001 larstha                 0.0  0.0 1:33 34564    1804    1823 goa-identity-se
-   1916 1001 larstha                 0.0  0.0 1:33 33936    1804    1916 gvfs-afc-volume
-   1925 1001 larstha                 0.0  0.0 1:33 26124    1804    1925 gvfs-gphoto2-vo
-   1938 1001 larstha                 0.0  0.0 1:33 17216    1789    1938 gdm-wayland-ses
-   1943 1001 larstha                 0.0  0.0 1:33 17924    1938    1938 gnome-session-b
-   1985 1001 larstha                 0.0  0.0 1:33  8836    1804    1985 gnome-session-c
-   1997 1001 larstha                 0.0  0.1 1:33 52144    1804    1997 gnome-session-b
-   2019 1001 larstha                 0.6  2.2 1:33 375812   1804    2019 gnome-shell
-   2020 1001 larstha                 0.0  0.0 1:33 33988    1997    1997 at-spi-bus-laun
-   2028 1001 larstha                 0.0  0.0 1:33   788    2020    1997 dbus-daemon
-   2136 1001 larstha                 0.0  0.0 1:33 17372    1804    2136 gvfsd-metadata
-   2144 1001 larstha                 0.0  0.1 1:33 60144    1804    1823 gnome-shell-cal
-   2150 1001 larstha                 0.0  0.1 1:33 61688    1804    2150 evolution-sourc
-   2163 1001 larstha                 0.0  0.0 1:33 17460    1804    2163 dconf-service
-   2168 1001 larstha                 0.0  0.1 1:33 103436   1804    2168 evolution-calen
-   2183 1001 larstha                 0.0  0.1 1:33 77172    1804    2183 evolution-addre
-   2198 1001 larstha                 0.0  0.1 1:33 56024    1804    1823 gjs
-   2200 1001 larstha                 0.0  0.0 1:33 17364    1804    1997 at-spi2-registr
-   2208 1001 larstha                 0.0  0.0 1:33 34376    1834    1834 gvfsd-trash
-   2222 1001 larstha                 0.0  0.0 1:33   364    1804    2222 sh
-   2223 1001 larstha                 0.0  0.0 1:33 34020    1804    2223 gsd-a11y-settin
-   2225 1001 larstha                 0.0  0.0 1:33 38596    2222    2222 ibus-daemon
-   2226 1001 larstha                 0.0  0.1 1:33 63708    1804    2226 gsd-color
-   2229 1001 larstha                 0.0  0.0 1:33 34656    1804    2229 gsd-datetime
-   2231 1001 larstha                 0.0  0.0 1:33 34200    1804    2231 gsd-housekeepin
-   2232 1001 larstha                 0.0  0.1 1:33 45964    1804    2232 gsd-keyboard
-   2233 1001 larstha                 0.0  0.1 1:33 46408    1804    2233 gsd-media-keys
-   2234 1001 larstha                 0.0  0.1 1:33 47436    1804    2234 gsd-power
-   2236 1001 larstha                 0.0  0.0 1:33 26092    1804    2236 gsd-print-notif
-   2238 1001 larstha                 0.0  0.0 1:33 50668    1804    2238 gsd-rfkill
-   2239 1001 larstha                 0.0  0.0 1:33 25560    1804    2239 gsd-screensaver
-   2240 1001 larstha                 0.0  0.0 1:33 51732    1804    2240 gsd-sharing
-   2241 1001 larstha                 0.0  0.0 1:33 42500    1804    2241 gsd-smartcard
-   2242 1001 larstha                 0.0  0.0 1:33 34220    1804    2242 gsd-sound
-   2243 1001 larstha                 0.0  0.1 1:33 46256    1804    2243 gsd-wacom
-   2303 1001 larstha                 0.0  0.0 1:33 17372    2225    2222 ibus-memconf
-   2305 1001 larstha                 0.0  0.1 1:33 43832    2225    2222 ibus-extension-
-   2308 1001 larstha                 0.0  0.0 1:33 25756    1804    1823 ibus-portal
-   2311 1001 larstha                 0.0  0.3 1:33 76628    1997    1997 evolution-alarm
-   2319 1001 larstha                 0.0  0.0 1:33 26612    1997    1997 gsd-disk-utilit
-   2375 1001 larstha                 0.0  1.7 1:33 321276   1804    1997 snap-store
-   2417 1001 larstha                 0.0  0.0 1:33 17820    2225    2222 ibus-engine-sim
-   2465 1001 larstha                 0.0  0.0 1:33 34612    1804    2236 gsd-printer
-   2520 1001 larstha                 0.0  0.0 1:33 76956    1804    2520 xdg-desktop-por
-   2530 1001 larstha                 0.0  0.1 1:33 68100    1804    2530 xdg-desktop-por
-   2555 1001 larstha                 0.0  0.1 1:33 48012    1804    1823 gjs
-   2573 1001 larstha                 0.0  0.1 1:33 39892    1804    2573 xdg-desktop-por
-   2636 0 root                    0.0  0.5 1:33 108880      1    2636 fwupd
-   2656 1001 larstha                 0.0  0.0 1:33  1280    1804    2656 snapd-desktop-i
-   2734 1001 larstha                 0.0  0.1 1:33 31484    2656    2656 snapd-desktop-i
-   3325 1001 larstha                 0.1  0.7 1:33 122884   2019    2019 Xwayland
-   3344 1001 larstha                 0.0  0.4 1:33 102844   1804    3344 gsd-xsettings
-   3375 1001 larstha                 0.0  0.1 1:33 23424    1804    3344 ibus-x11
-   3884 1001 larstha                 0.0  0.1 1:33 212236   1804    1823 snap
-   5131 1001 larstha                 0.0  0.1 1:33 48764    1997    1997 update-notifier
-   7780 1001 larstha                 0.0  0.0 1:33 26112    1834    1834 gvfsd-http
-   9221 1001 larstha                 0.0  0.4 1:33 73636    1804    9221 gnome-terminal-
-   9239 1001 larstha                 0.0  0.0 1:33  3636    9221    9239 bash
-  11438 1001 larstha                 0.0  0.8 1:33 236224   2019    2019 obsidian
-  11495 1001 larstha                 0.0  0.3 1:33  4920   11438    2019 obsidian
-  11496 1001 larstha                 0.0  0.2 1:33  4904   11438    2019 obsidian
-  11526 1001 larstha                 0.0  0.8 1:33 207856  11495    2019 obsidian
-  11531 1001 larstha                 0.0  0.4 1:33 63952   11438    2019 obsidian
-  11542 1001 larstha                 0.0  1.0 1:33 287796  11438    2019 obsidian
-  11543 1001 larstha                 0.0  1.2 1:33 337172  11438    2019 obsidian
-  12887 1001 larstha                 0.0  0.0 1:33  1076    1825    1824 ssh-agent
-  74536 1001 larstha                 0.0  0.0 1:33  3052    9221   74536 bash
-  80195 1001 larstha                 0.0  0.3 1:33 84612    1804    1823 gnome-calendar
-  80199 1001 larstha                 0.0  0.2 1:33 46812     200    1823 seahorse
-  82329 1001 larstha                 0.5  4.1 1:33 1090880  2019    2019 firefox
-  82497 1001 larstha                 0.0  0.2 1:33 13656   82329    2019 Socket Process
-  82516 1001 larstha                 0.0  0.6 1:33 82080   82329    2019 Privileged Cont
-  82554 1001 larstha                 0.0  1.6 1:33 358988  82329    2019 Isolated Web Co
-  82558 1001 larstha                 0.0  1.9 1:33 331480  82329    2019 Isolated Web Co
-  82562 1001 larstha                 0.0  2.7 1:33 541812  82329    2019 Isolated Web Co
-  82572 1001 larstha                 0.0  1.9 1:33 323628  82329    2019 Isolated Web Co
-  82584 1001 larstha                 0.0  0.6 1:33 62756   82329    2019 Isolated Web Co
-  82605 1001 larstha                 0.0  1.3 1:33 208208  82329    2019 Isolated Web Co
-  82631 1001 larstha                 0.0  0.9 1:33 112432  82329    2019 Isolated Web Co
-  82652 1001 larstha                 0.0  2.1 1:33 483464  82329    2019 Isolated Web Co
-  82680 1001 larstha                 0.0  2.0 1:33 333032  82329    2019 Isolated Web Co
-  82732 1001 larstha                 0.0  1.9 1:33 338896  82329    2019 Isolated Web Co
-  83002 1001 larstha                 0.0  1.0 1:33 261228  82329    2019 WebExtensions
-  83286 1001 larstha                 0.0  2.3 1:33 425108  82329    2019 Isolated Web Co
-  83326 1001 larstha                 0.0  1.1 1:33 160964  82329    2019 Isolated Web Co
-  83332 1001 larstha                 0.0  0.2 1:33 39804   82329    2019 RDD Process
-  83340 1001 larstha                 0.0  0.2 1:33 17728   82329    2019 Utility Process
-  83618 1001 larstha                 0.0  1.2 1:33 212360  82329    2019 Isolated Web Co
-  83689 1001 larstha                 0.0  1.0 1:33 136256  82329    2019 Isolated Web Co
-  83925 1001 larstha                 0.0  1.3 1:33 205144  82329    2019 Isolated Web Co
-  84013 1001 larstha                 0.0  1.0 1:33 141120  82329    2019 Isolated Web Co
-  84177 1001 larstha                 0.0  1.9 1:33 329400  82329    2019 Isolated Web Co
-  96883 1001 larstha                 0.0  1.0 1:33 174652  82329    2019 Isolated Web Co
-  97718 1001 larstha                 0.0  0.8 1:33 107784  82329    2019 Isolated Web Co
-  99395 1001 larstha                 0.0  0.7 1:33 78764   82329    2019 Isolated Web Co
-  99587 1001 larstha                 0.0  0.8 1:33 106744  82329    2019 Isolated Web Co
- 103356 1001 larstha                 0.0  0.7 1:33 77912   82329    2019 Isolated Web Co
- 103359 1001 larstha                 0.0  0.8 1:33 111172  82329    2019 Isolated Web Co
- 103470 1001 larstha                 0.0  0.7 1:33 99448   82329    2019 file:// Content
- 104433 1001 larstha                 0.0  3.5 1:33 669636  82329    2019 Isolated Web Co
- 104953 1001 larstha                 0.0  2.7 1:33 399200  82329    2019 Isolated Web Co
- 116260 1001 larstha                 0.0  0.8 1:33 103444  82329    2019 Isolated Web Co
- 116296 1001 larstha                 0.0  0.7 1:33 80048   82329    2019 Isolated Web Co
- 116609 1001 larstha                 0.0  0.7 1:33 99424   82329    2019 Isolated Web Co
- 116645 1001 larstha                 0.0  0.7 1:33 78512   82329    2019 Isolated Web Co
- 116675 1001 larstha                 0.0  1.1 1:33 150372  82329    2019 Isolated Web Co
- 116997 1001 larstha                 0.0  1.8 1:33 280516  82329    2019 Isolated Web Co
- 119104 1001 larstha                 0.0  1.1 1:33 191908  82329    2019 Isolated Web Co
- 119151 1001 larstha                 0.0  1.0 1:33 147144  82329    2019 Isolated Web Co
- 128778 1001 larstha                 0.1  0.4 1:33 78964    2019    2019 emacs
- 132391 1001 larstha                 0.0  0.8 1:33 101260  82329    2019 Isolated Web Co
- 133097 1001 larstha                 0.1  1.3 1:33 278532  82329    2019 Isolated Web Co
- 134154 1001 larstha                 0.0  0.6 1:33 64788   82329    2019 Isolated Web Co
- 135609 1001 larstha                 0.0  0.7 1:33 77260   82329    2019 Isolated Web Co
- 136169 0 root                    0.0  0.0 1:33     0       2       0 kworker/u17:1-i915_flip
- 140722 1001 larstha                 0.0  0.8 1:33 96308   82329    2019 Isolated Web Co
- 142642 0 root                    0.0  0.0 1:33     0       2       0 kworker/u17:0-i915_flip
- 144346 0 root                    0.0  0.0 1:33     0       2       0 kworker/1:1-events
- 144602 0 root                    0.0  0.0 1:33     0       2       0 kworker/u16:57-events_unbound
- 144609 0 root                    0.0  0.0 1:33     0       2       0 kworker/u16:64-events_power_efficient
- 144624 0 root                    0.0  0.0 1:33     0       2       0 irq/185-mei_me
- 144736 0 root                    0.0  0.0 1:33  7960       1  144736 cupsd
- 144754 0 root                    0.0  0.0 1:33 18104       1  144754 cups-browsed
- 145490 1001 larstha                 0.0  0.5 1:33 84372    2019    2019 gjs
- 145716 0 root                    0.0  0.0 1:33     0       2       0 kworker/7:2-events
- 146289 0 root                    0.0  0.0 1:33     0       2       0 kworker/u16:0-events_power_efficient
- 146290 0 root                    0.1  0.0 1:33     0       2       0 kworker/6:1-events
- 146342 0 root                    0.0  0.0 1:33     0       2       0 kworker/2:1-events
- 146384 0 root                    0.0  0.0 1:33     0       2       0 kworker/5:0-events
- 146735 0 root                    0.0  0.0 1:33     0       2       0 kworker/0:0-events
- 146791 0 root                    0.0  0.0 1:33     0       2       0 kworker/1:2-events
- 147017 0 root                    0.0  0.0 1:33     0       2       0 kworker/4:2-events
- 147313 0 root                    0.0  0.0 1:33     0       2       0 kworker/3:2-events
- 147413 0 root                    0.0  0.0 1:33     0       2       0 kworker/7:0-mm_percpu_wq
- 147421 0 root                    0.0  0.0 1:33     0       2       0 kworker/6:2-inet_frag_wq
- 147709 0 root                    0.0  0.0 1:33     0       2       0 kworker/2:2-events
- 147914 0 root                    0.0  0.0 1:33     0       2       0 kworker/5:2-events
- 147916 0 root                    0.0  0.0 1:33     0       2       0 kworker/4:0-events
- 147954 0 root                    0.0  0.0 1:33     0       2       0 kworker/1:3-mm_percpu_wq
- 148064 0 root                    0.0  0.0 1:33     0       2       0 kworker/3:0-events
- 148065 0 root                    0.0  0.0 1:33     0       2       0 kworker/0:2-events
- 148141 0 root                    0.0  0.0 1:33     0       2       0 kworker/7:1-events
- 148142 0 root                    0.0  0.0 1:33     0       2       0 kworker/u17:2
- 148173 0 root                    0.1  0.0 1:33     0       2       0 kworker/6:0-events
- 148253 0 root                    0.0  0.0 1:33     0       2       0 kworker/2:0
- 148259 1001 larstha                 0.0  0.4 1:33 45648   82329    2019 Isolated Servic
- 148284 0 root                    0.0  0.0 1:33     0       2       0 kworker/u16:1-events_power_efficient
- 148286 0 root                    0.0  0.0 1:33     0       2       0 kworker/4:1-events_freezable
- 148299 1001 larstha                 0.0  0.4 1:33 38948   82329    2019 Web Content
- 148301 1001 larstha                 0.0  0.4 1:33 38952   82329    2019 Web Content
- 148367 0 root                    0.1  0.0 1:33     0       2       0 kworker/3:1-events
- 148371 0 root                    0.0  0.0 1:33     0       2       0 kworker/5:1-events
- 148378 1001 larstha                 0.4  0.3 1:33 38968   82329    2019 Web Content
- 148406 1001 larstha                 0.0  0.0 1:33  1100    9239    9239 ps
+    // Subsequently added synthetic stat column
+    // Subsequently added synthetic rss column
+    // pid user                pcpu pmem  cputimes size     rss      ppid    sess stat command
+    let text = "1 0 root 0.0 0.0 1:28 21516 17212 0 1 S systemd
+2 0 root 0.0 0.0 1:28 0 0 0 0 S kthreadd
+3 0 root 0.0 0.0 1:28 0 0 2 0 S rcu_gp
+4 0 root 0.0 0.0 1:28 0 0 2 0 S rcu_par_gp
+5 0 root 0.0 0.0 1:28 0 0 2 0 S slub_flushwq
+6 0 root 0.0 0.0 1:28 0 0 2 0 S netns
+8 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/0:0H-events_highpri
+10 0 root 0.0 0.0 1:28 0 0 2 0 S mm_percpu_wq
+11 0 root 0.0 0.0 1:28 0 0 2 0 S rcu_tasks_kthread
+12 0 root 0.0 0.0 1:28 0 0 2 0 S rcu_tasks_rude_kthread
+13 0 root 0.0 0.0 1:28 0 0 2 0 S rcu_tasks_trace_kthread
+14 0 root 0.0 0.0 1:28 0 0 2 0 S ksoftirqd/0
+15 0 root 0.0 0.0 1:28 0 0 2 0 S rcu_preempt
+16 0 root 0.0 0.0 1:28 0 0 2 0 S migration/0
+17 0 root 0.0 0.0 1:28 0 0 2 0 S idle_inject/0
+19 0 root 0.0 0.0 1:28 0 0 2 0 S cpuhp/0
+20 0 root 0.0 0.0 1:28 0 0 2 0 S cpuhp/1
+21 0 root 0.0 0.0 1:28 0 0 2 0 S idle_inject/1
+22 0 root 0.0 0.0 1:28 0 0 2 0 S migration/1
+23 0 root 0.0 0.0 1:28 0 0 2 0 S ksoftirqd/1
+25 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/1:0H-events_highpri
+26 0 root 0.0 0.0 1:28 0 0 2 0 S cpuhp/2
+27 0 root 0.0 0.0 1:28 0 0 2 0 S idle_inject/2
+28 0 root 0.0 0.0 1:28 0 0 2 0 S migration/2
+29 0 root 0.0 0.0 1:28 0 0 2 0 S ksoftirqd/2
+31 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/2:0H-events_highpri
+32 0 root 0.0 0.0 1:28 0 0 2 0 S cpuhp/3
+33 0 root 0.0 0.0 1:28 0 0 2 0 S idle_inject/3
+34 0 root 0.0 0.0 1:28 0 0 2 0 S migration/3
+35 0 root 0.0 0.0 1:28 0 0 2 0 S ksoftirqd/3
+37 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/3:0H-events_highpri
+38 0 root 0.0 0.0 1:28 0 0 2 0 S cpuhp/4
+39 0 root 0.0 0.0 1:28 0 0 2 0 S idle_inject/4
+40 0 root 0.0 0.0 1:28 0 0 2 0 S migration/4
+41 0 root 0.0 0.0 1:28 0 0 2 0 S ksoftirqd/4
+43 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/4:0H-kblockd
+44 0 root 0.0 0.0 1:28 0 0 2 0 S cpuhp/5
+45 0 root 0.0 0.0 1:28 0 0 2 0 S idle_inject/5
+46 0 root 0.0 0.0 1:28 0 0 2 0 S migration/5
+47 0 root 0.0 0.0 1:28 0 0 2 0 S ksoftirqd/5
+49 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/5:0H-events_highpri
+50 0 root 0.0 0.0 1:28 0 0 2 0 S cpuhp/6
+51 0 root 0.0 0.0 1:28 0 0 2 0 S idle_inject/6
+52 0 root 0.0 0.0 1:28 0 0 2 0 S migration/6
+53 0 root 0.0 0.0 1:28 0 0 2 0 S ksoftirqd/6
+55 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/6:0H-events_highpri
+56 0 root 0.0 0.0 1:28 0 0 2 0 S cpuhp/7
+57 0 root 0.0 0.0 1:28 0 0 2 0 S idle_inject/7
+58 0 root 0.0 0.0 1:28 0 0 2 0 S migration/7
+59 0 root 0.0 0.0 1:28 0 0 2 0 S ksoftirqd/7
+61 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/7:0H-events_highpri
+62 0 root 0.0 0.0 1:28 0 0 2 0 S kdevtmpfs
+63 0 root 0.0 0.0 1:28 0 0 2 0 S inet_frag_wq
+64 0 root 0.0 0.0 1:28 0 0 2 0 S kauditd
+65 0 root 0.0 0.0 1:28 0 0 2 0 S khungtaskd
+67 0 root 0.0 0.0 1:28 0 0 2 0 S oom_reaper
+69 0 root 0.0 0.0 1:28 0 0 2 0 S writeback
+70 0 root 0.0 0.0 1:28 0 0 2 0 S kcompactd0
+71 0 root 0.0 0.0 1:28 0 0 2 0 S ksmd
+72 0 root 0.0 0.0 1:28 0 0 2 0 S khugepaged
+73 0 root 0.0 0.0 1:28 0 0 2 0 S kintegrityd
+74 0 root 0.0 0.0 1:28 0 0 2 0 S kblockd
+75 0 root 0.0 0.0 1:28 0 0 2 0 S blkcg_punt_bio
+78 0 root 0.0 0.0 1:28 0 0 2 0 S tpm_dev_wq
+79 0 root 0.0 0.0 1:28 0 0 2 0 S ata_sff
+81 0 root 0.0 0.0 1:28 0 0 2 0 S md
+82 0 root 0.0 0.0 1:28 0 0 2 0 S edac-poller
+83 0 root 0.0 0.0 1:28 0 0 2 0 S devfreq_wq
+84 0 root 0.0 0.0 1:28 0 0 2 0 S watchdogd
+85 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/0:1H-acpi_thermal_pm
+86 0 root 0.0 0.0 1:28 0 0 2 0 S kswapd0
+87 0 root 0.0 0.0 1:28 0 0 2 0 S ecryptfs-kthread
+93 0 root 0.0 0.0 1:28 0 0 2 0 S kthrotld
+98 0 root 0.0 0.0 1:28 0 0 2 0 S irq/124-pciehp
+99 0 root 0.0 0.0 1:28 0 0 2 0 S irq/125-pciehp
+104 0 root 0.0 0.0 1:28 0 0 2 0 S acpi_thermal_pm
+105 0 root 0.0 0.0 1:28 0 0 2 0 S xenbus_probe
+107 0 root 0.0 0.0 1:28 0 0 2 0 S vfio-irqfd-clea
+108 0 root 0.0 0.0 1:28 0 0 2 0 S mld
+109 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/5:1H-kblockd
+110 0 root 0.0 0.0 1:28 0 0 2 0 S ipv6_addrconf
+115 0 root 0.0 0.0 1:28 0 0 2 0 S kstrp
+121 0 root 0.0 0.0 1:28 0 0 2 0 S zswap-shrink
+170 0 root 0.0 0.0 1:28 0 0 2 0 S charger_manager
+208 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/7:1H-events_highpri
+229 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/3:1H-events_highpri
+231 0 root 0.0 0.0 1:28 0 0 2 0 S nvme-wq
+232 0 root 0.0 0.0 1:28 0 0 2 0 S nvme-reset-wq
+233 0 root 0.0 0.0 1:28 0 0 2 0 S nvme-delete-wq
+238 0 root 0.0 0.0 1:28 0 0 2 0 S irq/173-SYNA30B7:00
+239 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/2:1H-events_highpri
+243 0 root 0.0 0.0 1:28 0 0 2 0 S irq/174-WACF4233:00
+267 0 root 0.0 0.0 1:28 0 0 2 0 S jbd2/nvme0n1p2-8
+268 0 root 0.0 0.0 1:28 0 0 2 0 S ext4-rsv-conver
+303 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/6:1H-kblockd
+308 0 root 0.0 0.3 1:28 18052 14441 1 308 S systemd-journal
+335 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/4:1H-events_highpri
+336 0 root 0.0 0.0 1:28 0 0 2 0 S kworker/1:1H-events_highpri
+339 0 root 0.0 0.0 1:28 2676 2140 1 339 S systemd-udevd
+469 0 root 0.0 0.0 1:28 0 0 2 0 S cfg80211
+485 0 root 0.0 0.0 1:28 0 0 2 0 S irq/175-iwlwifi:default_queue
+488 0 root 0.0 0.0 1:28 0 0 2 0 S irq/176-iwlwifi:queue_1
+489 0 root 0.0 0.0 1:28 0 0 2 0 S irq/177-iwlwifi:queue_2
+490 0 root 0.0 0.0 1:28 0 0 2 0 S irq/178-iwlwifi:queue_3
+491 0 root 0.0 0.0 1:28 0 0 2 0 S irq/179-iwlwifi:queue_4
+492 0 root 0.0 0.0 1:28 0 0 2 0 S irq/180-iwlwifi:queue_5
+493 0 root 0.0 0.0 1:28 0 0 2 0 S irq/181-iwlwifi:queue_6
+494 0 root 0.0 0.0 1:28 0 0 2 0 S irq/182-iwlwifi:queue_7
+496 0 root 0.0 0.0 1:28 0 0 2 0 S irq/183-iwlwifi:queue_8
+498 0 root 0.0 0.0 1:28 0 0 2 0 S irq/184-iwlwifi:exception
+512 1 systemd-oom 0.0 0.0 1:33 740 592 1 512 S systemd-oomd
+513 2 systemd-resolve 0.0 0.0 1:33 5204 4163 1 513 S systemd-resolve
+514 3 systemd-timesync 0.0 0.0 1:33 8944 7155 1 514 S systemd-timesyn
+535 0 root 0.0 0.0 1:33 0 0 2 0 S cryptd
+581 0 root 0.0 0.0 1:33 25828 20662 1 581 S accounts-daemon
+584 0 root 0.0 0.0 1:33 360 288 1 584 S acpid
+587 4 avahi 0.0 0.0 1:33 636 508 1 587 S avahi-daemon
+589 0 root 0.0 0.0 1:33 440 352 1 589 S cron
+590 5 messagebus 0.0 0.0 1:33 3512 2809 1 590 S dbus-daemon
+592 0 root 0.0 0.1 1:33 28332 22665 1 592 S NetworkManager
+602 0 root 0.0 0.0 1:33 8916 7132 1 602 S irqbalance
+616 0 root 0.0 0.1 1:33 10896 8716 1 616 S networkd-dispat
+617 0 root 0.0 0.0 1:33 28820 23056 1 617 S polkitd
+618 0 root 0.0 0.0 1:33 25796 20636 1 618 S power-profiles-
+619 6 syslog 0.0 0.0 1:33 18708 14966 1 619 S rsyslogd
+621 0 root 0.0 0.2 1:33 263568 210854 1 621 S snapd
+626 0 root 0.0 0.0 1:33 25828 20662 1 626 S switcheroo-cont
+643 0 root 0.0 0.0 1:33 33780 27024 1 643 S systemd-logind
+654 0 root 0.0 0.0 1:33 25984 20787 1 654 S thermald
+655 0 root 0.0 0.0 1:33 43880 35104 1 655 S udisksd
+677 0 root 0.0 0.0 1:33 2020 1616 1 677 S wpa_supplicant
+687 4 avahi 0.0 0.0 1:33 448 358 587 587 S avahi-daemon
+719 0 root 0.0 0.0 1:33 34868 27894 1 719 S ModemManager
+722 0 root 0.0 0.0 1:33 25764 20611 1 722 S boltd
+751 0 root 0.0 0.1 1:33 18004 14403 1 751 S unattended-upgr
+757 0 root 0.0 0.0 1:33 26100 20880 1 757 S gdm3
+761 0 root 0.0 0.0 1:33 32580 26064 1 761 S iio-sensor-prox
+792 0 root 0.0 0.0 1:33 584 467 1 792 S bluetoothd
+799 0 root 0.0 0.0 1:33 0 0 2 0 S card0-crtc0
+800 0 root 0.0 0.0 1:33 0 0 2 0 S card0-crtc1
+801 0 root 0.0 0.0 1:33 0 0 2 0 S card0-crtc2
+802 0 root 0.0 0.0 1:33 0 0 2 0 S card0-crtc3
+960 0 root 0.0 0.0 1:33 0 0 2 0 S irq/207-AudioDSP
+1079 7 rtkit 0.0 0.0 1:33 17076 13660 1 1079 S rtkit-daemon
+1088 0 root 0.0 0.0 1:33 26144 20915 1 1088 S upowerd
+1352 0 root 0.0 0.2 1:33 50776 40620 1 1352 S packagekitd
+1523 8 colord 0.0 0.0 1:33 28708 22966 1 1523 S colord
+1618 9 kernoops 0.0 0.0 1:33 520 416 1 1618 S kerneloops
+1622 9 kernoops 0.0 0.0 1:33 520 416 1 1622 S kerneloops
+1789 0 root 0.0 0.0 1:33 35428 28342 757 757 S gdm-session-wor
+1804 1001 larstha 0.0 0.0 1:33 2216 1772 1 1804 S systemd
+1805 1001 larstha 0.0 0.0 1:33 20556 16444 1804 1804 S (sd-pam)
+1811 1001 larstha 0.0 0.0 1:33 25636 20508 1804 1811 S pipewire
+1812 1001 larstha 0.0 0.0 1:33 9256 7404 1804 1812 S pipewire-media-
+1813 1001 larstha 0.1 0.1 1:33 72012 57609 1804 1813 S pulseaudio
+1823 1001 larstha 0.0 0.0 1:33 2624 2099 1804 1823 S dbus-daemon
+1825 1001 larstha 0.0 0.0 1:33 59244 47395 1 1824 S gnome-keyring-d
+1834 1001 larstha 0.0 0.0 1:33 25792 20633 1804 1834 S gvfsd
+1840 1001 larstha 0.0 0.0 1:33 44420 35536 1804 1834 S gvfsd-fuse
+1855 1001 larstha 0.0 0.0 1:33 60976 48780 1804 1855 S xdg-document-po
+1859 1001 larstha 0.0 0.0 1:33 25536 20428 1804 1859 S xdg-permission-
+1865 0 root 0.0 0.0 1:33 356 284 1855 1865 S fusermount3
+1884 1001 larstha 0.0 0.1 1:33 151232 120985 1804 1884 S tracker-miner-f
+1892 0 root 0.0 0.0 1:33 0 0 2 0 S krfcommd
+1894 1001 larstha 0.0 0.0 1:33 35316 28252 1804 1894 S gvfs-udisks2-vo
+1899 1001 larstha 0.0 0.0 1:33 25708 20566 1804 1899 S gvfs-mtp-volume
+1903 1001 larstha 0.0 0.0 1:33 25688 20550 1804 1903 S gvfs-goa-volume
+1907 1001 larstha 0.0 0.2 1:33 44544 35635 1804 1823 S goa-daemon
+1914 1001 larstha 0.0 0.0 1:33 34564 27651 1804 1823 S goa-identity-se
+1916 1001 larstha 0.0 0.0 1:33 33936 27148 1804 1916 S gvfs-afc-volume
+1925 1001 larstha 0.0 0.0 1:33 26124 20899 1804 1925 S gvfs-gphoto2-vo
+1938 1001 larstha 0.0 0.0 1:33 17216 13772 1789 1938 S gdm-wayland-ses
+1943 1001 larstha 0.0 0.0 1:33 17924 14339 1938 1938 S gnome-session-b
+1985 1001 larstha 0.0 0.0 1:33 8836 7068 1804 1985 S gnome-session-c
+1997 1001 larstha 0.0 0.1 1:33 52144 41715 1804 1997 S gnome-session-b
+2019 1001 larstha 0.6 2.2 1:33 375812 300649 1804 2019 S gnome-shell
+2020 1001 larstha 0.0 0.0 1:33 33988 27190 1997 1997 S at-spi-bus-laun
+2028 1001 larstha 0.0 0.0 1:33 788 630 2020 1997 S dbus-daemon
+2136 1001 larstha 0.0 0.0 1:33 17372 13897 1804 2136 S gvfsd-metadata
+2144 1001 larstha 0.0 0.1 1:33 60144 48115 1804 1823 S gnome-shell-cal
+2150 1001 larstha 0.0 0.1 1:33 61688 49350 1804 2150 S evolution-sourc
+2163 1001 larstha 0.0 0.0 1:33 17460 13968 1804 2163 S dconf-service
+2168 1001 larstha 0.0 0.1 1:33 103436 82748 1804 2168 S evolution-calen
+2183 1001 larstha 0.0 0.1 1:33 77172 61737 1804 2183 S evolution-addre
+2198 1001 larstha 0.0 0.1 1:33 56024 44819 1804 1823 S gjs
+2200 1001 larstha 0.0 0.0 1:33 17364 13891 1804 1997 S at-spi2-registr
+2208 1001 larstha 0.0 0.0 1:33 34376 27500 1834 1834 S gvfsd-trash
+2222 1001 larstha 0.0 0.0 1:33 364 291 1804 2222 S sh
+2223 1001 larstha 0.0 0.0 1:33 34020 27216 1804 2223 S gsd-a11y-settin
+2225 1001 larstha 0.0 0.0 1:33 38596 30876 2222 2222 S ibus-daemon
+2226 1001 larstha 0.0 0.1 1:33 63708 50966 1804 2226 S gsd-color
+2229 1001 larstha 0.0 0.0 1:33 34656 27724 1804 2229 S gsd-datetime
+2231 1001 larstha 0.0 0.0 1:33 34200 27360 1804 2231 S gsd-housekeepin
+2232 1001 larstha 0.0 0.1 1:33 45964 36771 1804 2232 S gsd-keyboard
+2233 1001 larstha 0.0 0.1 1:33 46408 37126 1804 2233 S gsd-media-keys
+2234 1001 larstha 0.0 0.1 1:33 47436 37948 1804 2234 S gsd-power
+2236 1001 larstha 0.0 0.0 1:33 26092 20873 1804 2236 S gsd-print-notif
+2238 1001 larstha 0.0 0.0 1:33 50668 40534 1804 2238 S gsd-rfkill
+2239 1001 larstha 0.0 0.0 1:33 25560 20448 1804 2239 S gsd-screensaver
+2240 1001 larstha 0.0 0.0 1:33 51732 41385 1804 2240 S gsd-sharing
+2241 1001 larstha 0.0 0.0 1:33 42500 34000 1804 2241 S gsd-smartcard
+2242 1001 larstha 0.0 0.0 1:33 34220 27376 1804 2242 S gsd-sound
+2243 1001 larstha 0.0 0.1 1:33 46256 37004 1804 2243 S gsd-wacom
+2303 1001 larstha 0.0 0.0 1:33 17372 13897 2225 2222 S ibus-memconf
+2305 1001 larstha 0.0 0.1 1:33 43832 35065 2225 2222 S ibus-extension-
+2308 1001 larstha 0.0 0.0 1:33 25756 20604 1804 1823 S ibus-portal
+2311 1001 larstha 0.0 0.3 1:33 76628 61302 1997 1997 S evolution-alarm
+2319 1001 larstha 0.0 0.0 1:33 26612 21289 1997 1997 S gsd-disk-utilit
+2375 1001 larstha 0.0 1.7 1:33 321276 257020 1804 1997 S snap-store
+2417 1001 larstha 0.0 0.0 1:33 17820 14256 2225 2222 S ibus-engine-sim
+2465 1001 larstha 0.0 0.0 1:33 34612 27689 1804 2236 S gsd-printer
+2520 1001 larstha 0.0 0.0 1:33 76956 61564 1804 2520 S xdg-desktop-por
+2530 1001 larstha 0.0 0.1 1:33 68100 54480 1804 2530 S xdg-desktop-por
+2555 1001 larstha 0.0 0.1 1:33 48012 38409 1804 1823 S gjs
+2573 1001 larstha 0.0 0.1 1:33 39892 31913 1804 2573 S xdg-desktop-por
+2636 0 root 0.0 0.5 1:33 108880 87104 1 2636 S fwupd
+2656 1001 larstha 0.0 0.0 1:33 1280 1024 1804 2656 S snapd-desktop-i
+2734 1001 larstha 0.0 0.1 1:33 31484 25187 2656 2656 S snapd-desktop-i
+3325 1001 larstha 0.1 0.7 1:33 122884 98307 2019 2019 S Xwayland
+3344 1001 larstha 0.0 0.4 1:33 102844 82275 1804 3344 S gsd-xsettings
+3375 1001 larstha 0.0 0.1 1:33 23424 18739 1804 3344 S ibus-x11
+3884 1001 larstha 0.0 0.1 1:33 212236 169788 1804 1823 S snap
+5131 1001 larstha 0.0 0.1 1:33 48764 39011 1997 1997 S update-notifier
+7780 1001 larstha 0.0 0.0 1:33 26112 20889 1834 1834 S gvfsd-http
+9221 1001 larstha 0.0 0.4 1:33 73636 58908 1804 9221 S gnome-terminal-
+9239 1001 larstha 0.0 0.0 1:33 3636 2908 9221 9239 S bash
+11438 1001 larstha 0.0 0.8 1:33 236224 188979 2019 2019 S obsidian
+11495 1001 larstha 0.0 0.3 1:33 4920 3936 11438 2019 S obsidian
+11496 1001 larstha 0.0 0.2 1:33 4904 3923 11438 2019 S obsidian
+11526 1001 larstha 0.0 0.8 1:33 207856 166284 11495 2019 S obsidian
+11531 1001 larstha 0.0 0.4 1:33 63952 51161 11438 2019 S obsidian
+11542 1001 larstha 0.0 1.0 1:33 287796 230236 11438 2019 S obsidian
+11543 1001 larstha 0.0 1.2 1:33 337172 269737 11438 2019 S obsidian
+12887 1001 larstha 0.0 0.0 1:33 1076 860 1825 1824 S ssh-agent
+74536 1001 larstha 0.0 0.0 1:33 3052 2441 9221 74536 S bash
+80195 1001 larstha 0.0 0.3 1:33 84612 67689 1804 1823 S gnome-calendar
+80199 1001 larstha 0.0 0.2 1:33 46812 37449 200 1823 S seahorse
+82329 1001 larstha 0.5 4.1 1:33 1090880 872704 2019 2019 S firefox
+82497 1001 larstha 0.0 0.2 1:33 13656 10924 82329 2019 S Socket Process
+82516 1001 larstha 0.0 0.6 1:33 82080 65664 82329 2019 S Privileged Cont
+82554 1001 larstha 0.0 1.6 1:33 358988 287190 82329 2019 S Isolated Web Co
+82558 1001 larstha 0.0 1.9 1:33 331480 265184 82329 2019 S Isolated Web Co
+82562 1001 larstha 0.0 2.7 1:33 541812 433449 82329 2019 S Isolated Web Co
+82572 1001 larstha 0.0 1.9 1:33 323628 258902 82329 2019 S Isolated Web Co
+82584 1001 larstha 0.0 0.6 1:33 62756 50204 82329 2019 S Isolated Web Co
+82605 1001 larstha 0.0 1.3 1:33 208208 166566 82329 2019 S Isolated Web Co
+82631 1001 larstha 0.0 0.9 1:33 112432 89945 82329 2019 S Isolated Web Co
+82652 1001 larstha 0.0 2.1 1:33 483464 386771 82329 2019 S Isolated Web Co
+82680 1001 larstha 0.0 2.0 1:33 333032 266425 82329 2019 S Isolated Web Co
+82732 1001 larstha 0.0 1.9 1:33 338896 271116 82329 2019 S Isolated Web Co
+83002 1001 larstha 0.0 1.0 1:33 261228 208982 82329 2019 S WebExtensions
+83286 1001 larstha 0.0 2.3 1:33 425108 340086 82329 2019 S Isolated Web Co
+83326 1001 larstha 0.0 1.1 1:33 160964 128771 82329 2019 S Isolated Web Co
+83332 1001 larstha 0.0 0.2 1:33 39804 31843 82329 2019 S RDD Process
+83340 1001 larstha 0.0 0.2 1:33 17728 14182 82329 2019 S Utility Process
+83618 1001 larstha 0.0 1.2 1:33 212360 169888 82329 2019 S Isolated Web Co
+83689 1001 larstha 0.0 1.0 1:33 136256 109004 82329 2019 S Isolated Web Co
+83925 1001 larstha 0.0 1.3 1:33 205144 164115 82329 2019 S Isolated Web Co
+84013 1001 larstha 0.0 1.0 1:33 141120 112896 82329 2019 S Isolated Web Co
+84177 1001 larstha 0.0 1.9 1:33 329400 263520 82329 2019 S Isolated Web Co
+96883 1001 larstha 0.0 1.0 1:33 174652 139721 82329 2019 S Isolated Web Co
+97718 1001 larstha 0.0 0.8 1:33 107784 86227 82329 2019 S Isolated Web Co
+99395 1001 larstha 0.0 0.7 1:33 78764 63011 82329 2019 S Isolated Web Co
+99587 1001 larstha 0.0 0.8 1:33 106744 85395 82329 2019 S Isolated Web Co
+103356 1001 larstha 0.0 0.7 1:33 77912 62329 82329 2019 S Isolated Web Co
+103359 1001 larstha 0.0 0.8 1:33 111172 88937 82329 2019 S Isolated Web Co
+103470 1001 larstha 0.0 0.7 1:33 99448 79558 82329 2019 S file:// Content
+104433 1001 larstha 0.0 3.5 1:33 669636 535708 82329 2019 S Isolated Web Co
+104953 1001 larstha 0.0 2.7 1:33 399200 319360 82329 2019 S Isolated Web Co
+116260 1001 larstha 0.0 0.8 1:33 103444 82755 82329 2019 S Isolated Web Co
+116296 1001 larstha 0.0 0.7 1:33 80048 64038 82329 2019 S Isolated Web Co
+116609 1001 larstha 0.0 0.7 1:33 99424 79539 82329 2019 S Isolated Web Co
+116645 1001 larstha 0.0 0.7 1:33 78512 62809 82329 2019 S Isolated Web Co
+116675 1001 larstha 0.0 1.1 1:33 150372 120297 82329 2019 S Isolated Web Co
+116997 1001 larstha 0.0 1.8 1:33 280516 224412 82329 2019 S Isolated Web Co
+119104 1001 larstha 0.0 1.1 1:33 191908 153526 82329 2019 S Isolated Web Co
+119151 1001 larstha 0.0 1.0 1:33 147144 117715 82329 2019 S Isolated Web Co
+128778 1001 larstha 0.1 0.4 1:33 78964 63171 2019 2019 S emacs
+132391 1001 larstha 0.0 0.8 1:33 101260 81008 82329 2019 S Isolated Web Co
+133097 1001 larstha 0.1 1.3 1:33 278532 222825 82329 2019 S Isolated Web Co
+134154 1001 larstha 0.0 0.6 1:33 64788 51830 82329 2019 S Isolated Web Co
+135609 1001 larstha 0.0 0.7 1:33 77260 61808 82329 2019 S Isolated Web Co
+136169 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/u17:1-i915_flip
+140722 1001 larstha 0.0 0.8 1:33 96308 77046 82329 2019 S Isolated Web Co
+142642 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/u17:0-i915_flip
+144346 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/1:1-events
+144602 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/u16:57-events_unbound
+144609 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/u16:64-events_power_efficient
+144624 0 root 0.0 0.0 1:33 0 0 2 0 S irq/185-mei_me
+144736 0 root 0.0 0.0 1:33 7960 6368 1 144736 S cupsd
+144754 0 root 0.0 0.0 1:33 18104 14483 1 144754 S cups-browsed
+145490 1001 larstha 0.0 0.5 1:33 84372 67497 2019 2019 S gjs
+145716 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/7:2-events
+146289 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/u16:0-events_power_efficient
+146290 0 root 0.1 0.0 1:33 0 0 2 0 S kworker/6:1-events
+146342 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/2:1-events
+146384 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/5:0-events
+146735 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/0:0-events
+146791 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/1:2-events
+147017 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/4:2-events
+147313 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/3:2-events
+147413 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/7:0-mm_percpu_wq
+147421 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/6:2-inet_frag_wq
+147709 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/2:2-events
+147914 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/5:2-events
+147916 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/4:0-events
+147954 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/1:3-mm_percpu_wq
+148064 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/3:0-events
+148065 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/0:2-events
+148141 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/7:1-events
+148142 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/u17:2
+148173 0 root 0.1 0.0 1:33 0 0 2 0 S kworker/6:0-events
+148253 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/2:0
+148259 1001 larstha 0.0 0.4 1:33 45648 36518 82329 2019 S Isolated Servic
+148284 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/u16:1-events_power_efficient
+148286 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/4:1-events_freezable
+148299 1001 larstha 0.0 0.4 1:33 38948 31158 82329 2019 S Web Content
+148301 1001 larstha 0.0 0.4 1:33 38952 31161 82329 2019 S Web Content
+148367 0 root 0.1 0.0 1:33 0 0 2 0 S kworker/3:1-events
+148371 0 root 0.0 0.0 1:33 0 0 2 0 S kworker/5:1-events
+148378 1001 larstha 0.4 0.3 1:33 38968 31174 82329 2019 S Web Content
+148406 1001 larstha 0.0 0.0 1:33 1100 880 9239 9239 S ps
 ";
     parse_ps_output(text)
 }
+
+#[test]
+fn test_rollup_process_tree() {
+    let processes = parsed_full_test_output();
+    let total_cputime: usize = processes.iter().map(|p| p.cputime_sec).sum();
+    let total_mem: usize = processes.iter().map(|p| p.mem_size_kib).sum();
+
+    let roots = rollup_process_tree(&processes);
+    // Nothing is dropped or double-counted, including the orphaned #80199 (seahorse, reparented
+    // to pid 1 because its real parent #200 isn't in this snapshot).
+    assert_eq!(
+        roots.iter().map(|r| r.cputime_sec).sum::<usize>(),
+        total_cputime
+    );
+    assert_eq!(
+        roots.iter().map(|r| r.mem_size_kib).sum::<usize>(),
+        total_mem
+    );
+
+    // pid 1's subtree includes the orphaned #80199 (seahorse, reparented because its real parent
+    // #200 isn't in this snapshot) as well as the whole gnome-shell/firefox tree hanging off
+    // larstha's systemd --user instance.
+    let init = roots.iter().find(|r| r.pid == 1).expect("pid 1 is a root");
+    assert!(init.mem_size_kib > 1090880 + 46812);
+}
+
+#[test]
+fn test_rollup_process_sessions() {
+    let processes = parsed_full_test_output();
+    let total_cputime: usize = processes.iter().map(|p| p.cputime_sec).sum();
+
+    let sessions = rollup_process_sessions(&processes);
+    assert_eq!(
+        sessions.iter().map(|s| s.cputime_sec).sum::<usize>(),
+        total_cputime
+    );
+
+    // Session 2019 is larstha's desktop session, home to gnome-shell, Xwayland, firefox, emacs...
+    let desktop = sessions
+        .iter()
+        .find(|s| s.pid == 2019)
+        .expect("session 2019 is a root");
+    assert!(desktop.mem_size_kib > 1090880);
+}
+
+#[test]
+fn test_fold_aggregating_commands() {
+    let processes = parsed_full_test_output();
+    let total_rss: usize = processes.iter().map(|p| p.rss_kib).sum();
+
+    let aggregating_commands = HashSet::from(["firefox", "obsidian"]);
+    let folded = fold_aggregating_commands(&processes, &aggregating_commands);
+
+    // Nothing is dropped or double-counted.
+    assert_eq!(folded.iter().map(|f| f.rss_kib).sum::<usize>(), total_rss);
+
+    // firefox (#82329) folds in all its "Isolated Web Co"/"Web Content"/... children.
+    let firefox = folded
+        .iter()
+        .find(|f| f.pid == 82329)
+        .expect("firefox is an aggregating root");
+    assert_eq!(firefox.command, "firefox");
+    assert!(firefox.child_count > 40);
+    assert!(firefox.rss_kib > 872704);
+
+    // obsidian (#11438) similarly folds in its helper processes.
+    let obsidian = folded
+        .iter()
+        .find(|f| f.pid == 11438)
+        .expect("obsidian is an aggregating root");
+    assert_eq!(obsidian.child_count, 6);
+
+    // A process unrelated to any aggregating root, like init, passes through unfolded.
+    let init = folded.iter().find(|f| f.pid == 1).expect("init survives");
+    assert_eq!(init.child_count, 0);
+
+    // Kernel threads (ppid 2) are never folded into a userspace aggregating root.
+    let kthread = folded
+        .iter()
+        .find(|f| f.command == "kthreadd")
+        .expect("kthreadd survives");
+    assert_eq!(kthread.child_count, 0);
+}
+
+#[test]
+fn test_fold_aggregating_commands_descendant_before_ancestor_in_input_order() {
+    // A firefox child listed *before* its own firefox ancestor in `processes` must still fold into
+    // a single group, not start a standalone `FoldedProcess` of its own just because it's the
+    // first unvisited match the outer loop sees.
+    fn p(pid: usize, ppid: usize, command: &str) -> Process {
+        Process {
+            pid,
+            uid: 1001,
+            user: "bob".to_string(),
+            cpu_pct: 1.0,
+            mem_pct: 1.0,
+            cputime_sec: 0,
+            mem_size_kib: 0,
+            rss_kib: 1000,
+            command: command.to_string(),
+            full_command: command.to_string(),
+            ppid,
+            session: 1,
+            state: 'S',
+        }
+    }
+    let processes = vec![
+        p(3, 2, "firefox"), // grandchild, also an aggregating command, listed before its ancestors
+        p(2, 1, "firefox"), // child, the same
+        p(1, 1, "firefox"), // root
+    ];
+    let aggregating_commands = HashSet::from(["firefox"]);
+    let folded = fold_aggregating_commands(&processes, &aggregating_commands);
+
+    assert_eq!(folded.len(), 1);
+    assert_eq!(folded[0].pid, 1);
+    assert_eq!(folded[0].child_count, 2);
+    assert_eq!(folded[0].rss_kib, 3000);
+}
@@ -0,0 +1,127 @@
+/// Collect information about logged-in user sessions from `who`.
+///
+/// This is a sibling of `sysinfo`: load average and disk usage tell us about the node as a whole,
+/// but on an HPC login node it's just as important to know who's actually logged in and from
+/// where, since that's where interactive load (editors, `screen`/`tmux`, stray `python` REPLs)
+/// comes from.
+use crate::command::{self, CmdError};
+
+use std::collections::HashSet;
+
+pub struct Session {
+    pub user: String,
+    pub tty: String,
+    pub login_time: String,
+    // The remote host/origin in parens after the login time, eg `galois.mathematik.uni-kl.de`;
+    // empty for a purely local session (a physical console, or `su`).  Normalized as described on
+    // `normalize_origin` below, so that the panes of one tmux server collapse to one origin.
+    pub remote_origin: String,
+}
+
+pub struct SessionSummary {
+    pub sessions: Vec<Session>,
+    // The number of distinct users in `sessions`, ie what `uptime`'s "N users" line reports; not
+    // the same as `sessions.len()`, since one user commonly holds many pts sessions at once.
+    pub active_users: usize,
+}
+
+const TIMEOUT_SECONDS: u64 = 2; // for `who`, as for `ps` in process.rs
+
+const WHO_COMMAND: &str = "who";
+
+/// Obtain the current set of logged-in sessions and the number of distinct active users.
+pub fn get_sessions() -> Result<SessionSummary, CmdError> {
+    match command::safe_command(WHO_COMMAND, TIMEOUT_SECONDS) {
+        Ok(out) => Ok(parse_who_output(&out)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Parse `who`'s default output:
+///
+///   koshy    pts/12       2024-10-31 11:17 (tmux(561857).%19)
+///   ise      pts/3        2024-10-31 09:02 (galois.mathematik.uni-kl.de)
+///   root     tty1         2024-10-31 08:00
+///
+/// into one `Session` per line, plus the count of distinct users across all of them.
+fn parse_who_output(raw_text: &str) -> SessionSummary {
+    let sessions = raw_text
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_ascii_whitespace().collect::<Vec<&str>>();
+            if fields.len() < 4 {
+                return None;
+            }
+            let user = fields[0].to_string();
+            let tty = fields[1].to_string();
+            let login_time = format!("{} {}", fields[2], fields[3]);
+            let remote_origin = fields
+                .get(4)
+                .map(|s| normalize_origin(s.trim_start_matches('(').trim_end_matches(')')))
+                .unwrap_or_default();
+            Some(Session {
+                user,
+                tty,
+                login_time,
+                remote_origin,
+            })
+        })
+        .collect::<Vec<Session>>();
+    let active_users = sessions
+        .iter()
+        .map(|s| s.user.as_str())
+        .collect::<HashSet<&str>>()
+        .len();
+    SessionSummary {
+        sessions,
+        active_users,
+    }
+}
+
+/// Strip the per-pane suffix off a tmux pseudo-origin, eg `tmux(561857).%19` -> `tmux(561857)`, so
+/// that the 20+ panes of one tmux server (which `who` otherwise reports as 20+ unrelated-looking
+/// origins) group together under a single origin.  Any other origin, eg a real remote hostname, is
+/// returned unchanged.
+fn normalize_origin(origin: &str) -> String {
+    match origin.find(").") {
+        Some(ix) if origin.starts_with("tmux(") => origin[..ix + 1].to_string(),
+        _ => origin.to_string(),
+    }
+}
+
+#[test]
+fn test_parse_who_output() {
+    let text = "\
+koshy    pts/12       2024-10-31 11:17 (tmux(561857).%19)
+koshy    pts/13       2024-10-31 11:18 (tmux(561857).%20)
+ise      pts/3        2024-10-31 09:02 (galois.mathematik.uni-kl.de)
+root     tty1         2024-10-31 08:00
+";
+    let summary = parse_who_output(text);
+    assert_eq!(summary.sessions.len(), 4);
+    assert_eq!(summary.active_users, 3);
+
+    assert_eq!(summary.sessions[0].user, "koshy");
+    assert_eq!(summary.sessions[0].tty, "pts/12");
+    assert_eq!(summary.sessions[0].login_time, "2024-10-31 11:17");
+    assert_eq!(summary.sessions[0].remote_origin, "tmux(561857)");
+    assert_eq!(summary.sessions[1].remote_origin, "tmux(561857)");
+
+    assert_eq!(summary.sessions[2].user, "ise");
+    assert_eq!(
+        summary.sessions[2].remote_origin,
+        "galois.mathematik.uni-kl.de"
+    );
+
+    assert_eq!(summary.sessions[3].user, "root");
+    assert_eq!(summary.sessions[3].remote_origin, "");
+}
+
+#[test]
+fn test_normalize_origin_passes_through_non_tmux() {
+    assert_eq!(
+        normalize_origin("galois.mathematik.uni-kl.de"),
+        "galois.mathematik.uni-kl.de"
+    );
+    assert_eq!(normalize_origin(""), "");
+}
@@ -0,0 +1,396 @@
+//! Process enumeration for macOS and FreeBSD via the `sysctl(3)` `KERN_PROC`/`KERN_PROC_ALL` MIB,
+//! giving the `systemapi::SystemAPI` abstraction a backend for hosts that have no `/proc` for
+//! procfs.rs to read from (cluster login nodes and developer machines are increasingly Apple
+//! Silicon, and some HPC sites also run FreeBSD).  `get_process_information` below returns the
+//! same `process::Process` records the Linux (procfs.rs) and `ps`-fallback (process.rs) paths do,
+//! so the rest of Sonar's output pipeline is unaffected by which of the three ran.
+//!
+//! `kinfo_proc` gives us pid/ppid/pgrp/uid/command and a decayed `p_pctcpu` estimate in one call,
+//! which is enough for the process list itself, but Apple does not consider `kinfo_proc` a stable
+//! cross-release ABI (unlike FreeBSD, which documents and versions it via `ki_structsize`) - see
+//! `<sys/sysctl.h>`'s own comment to that effect.  We therefore only trust it here for
+//! identity/enumeration (pid/ppid/pgrp/uid/command); precise cpu time and resident memory need
+//! `libproc`'s `proc_pidinfo`, a documented stable API, and are left as a follow-up - see the doc
+//! comment on `get_process_information` below.
+
+#![cfg(any(target_os = "macos", target_os = "freebsd"))]
+
+use crate::process::Process;
+
+use std::collections::HashMap;
+use std::mem;
+use std::ptr;
+
+type SizeT = cty::c_ulong;
+type PidT = i32;
+type UidT = u32;
+type GidT = u32;
+
+const CTL_KERN: cty::c_int = 1;
+const KERN_PROC: cty::c_int = 14;
+const KERN_PROC_ALL: cty::c_int = 0;
+
+extern "C" {
+    fn sysctl(
+        name: *mut cty::c_int,
+        namelen: cty::c_uint,
+        oldp: *mut std::ffi::c_void,
+        oldlenp: *mut SizeT,
+        newp: *mut std::ffi::c_void,
+        newlen: SizeT,
+    ) -> cty::c_int;
+}
+
+#[cfg(target_os = "macos")]
+mod darwin {
+    // Mirrors the subset of Darwin's `struct kinfo_proc` (`<sys/sysctl.h>`, built from
+    // `extern_proc` and `eproc` in `<sys/proc.h>`) that we need.  Apple does not guarantee this
+    // layout across major releases, so everything read through it (pid/ppid/pgrp/uid/command) is
+    // best-effort: `get_process_information` has no way to check the layout, and it does not fall
+    // back to the `ps`-based path in process.rs.
+    #[repr(C)]
+    pub struct ExternProc {
+        pub p_starttime: super::Timeval,
+        pub p_vmspace: u64,
+        pub p_sigacts: u64,
+        pub p_flag: cty::c_int,
+        pub p_stat: cty::c_char,
+        pub p_pid: PidT,
+        pub p_oppid: PidT,
+        pub p_dupfd: cty::c_int,
+        pub user_stack: u64,
+        pub exit_thread: u64,
+        pub p_debugger: cty::c_int,
+        pub sigwait: cty::c_int,
+        pub p_estcpu: cty::c_uint,
+        pub p_cpticks: cty::c_int,
+        pub p_pctcpu: cty::c_uint,
+        pub p_wchan: u64,
+        pub p_wmesg: u64,
+        pub p_swtime: cty::c_uint,
+        pub p_slptime: cty::c_uint,
+        pub p_realtimer: [u8; 32], // struct itimerval, unused here
+        pub p_rtime: super::Timeval,
+        pub p_uticks: u64,
+        pub p_sticks: u64,
+        pub p_iticks: u64,
+        pub p_traceflag: cty::c_int,
+        pub p_tracep: u64,
+        pub p_siglist: cty::c_int,
+        pub p_textvp: u64,
+        pub p_holdcnt: cty::c_int,
+        pub p_sigmask: u32,
+        pub p_sigignore: u32,
+        pub p_sigcatch: u32,
+        pub p_priority: u8,
+        pub p_usrpri: u8,
+        pub p_nice: cty::c_char,
+        pub p_comm: [cty::c_char; 17], // MAXCOMLEN + 1
+        pub p_pgrp: u64,
+        pub p_addr: u64,
+        pub p_xstat: u16,
+        pub p_acflag: u16,
+        pub p_ru: u64,
+    }
+
+    #[repr(C)]
+    pub struct Eproc {
+        pub e_paddr: u64,
+        pub e_sess: u64,
+        pub e_pcred: [u8; 16], // struct pcred's lock + fill, unused here
+        pub e_ucred_cr_ref: cty::c_int,
+        pub e_ucred_cr_uid: UidT,
+        pub e_ucred_cr_ngroups: cty::c_short,
+        pub e_ucred_cr_groups: [GidT; 16],
+        pub e_vm: [u8; 96], // struct vmspace summary, unused here
+        pub e_ppid: PidT,
+        pub e_pgid: PidT,
+        pub e_jobc: cty::c_short,
+        pub e_tdev: i32,
+        pub e_tpgid: PidT,
+        pub e_tsess: u64,
+        pub e_wmesg: [cty::c_char; 8],
+        pub e_xsize: i32,
+        pub e_xrssize: i16,
+        pub e_xccount: i16,
+        pub e_xswrss: i16,
+        pub e_flag: i32,
+        pub e_login: [cty::c_char; 12],
+        pub e_spare: [i32; 4],
+    }
+
+    #[repr(C)]
+    pub struct KinfoProc {
+        pub kp_proc: ExternProc,
+        pub kp_eproc: Eproc,
+    }
+
+    pub fn pid(p: &KinfoProc) -> usize {
+        p.kp_proc.p_pid as usize
+    }
+    pub fn ppid(p: &KinfoProc) -> usize {
+        p.kp_eproc.e_ppid as usize
+    }
+    pub fn pgrp(p: &KinfoProc) -> usize {
+        p.kp_eproc.e_pgid as usize
+    }
+    pub fn uid(p: &KinfoProc) -> usize {
+        p.kp_eproc.e_ucred_cr_uid as usize
+    }
+    pub fn comm(p: &KinfoProc) -> String {
+        super::c_array_to_string(&p.kp_proc.p_comm)
+    }
+}
+
+#[cfg(target_os = "freebsd")]
+mod freebsd {
+    // FreeBSD versions `struct kinfo_proc` explicitly via `ki_structsize` (`<sys/user.h>`) and
+    // documents it as part of the `sysctl(3)`/`libutil` ABI, so this is on firmer ground than the
+    // Darwin struct above.
+    #[repr(C)]
+    pub struct KinfoProc {
+        pub ki_structsize: cty::c_int,
+        pub ki_layout: cty::c_int,
+        pub ki_args: u64,
+        pub ki_paddr: u64,
+        pub ki_addr: u64,
+        pub ki_tracep: u64,
+        pub ki_textvp: u64,
+        pub ki_fd: u64,
+        pub ki_vmspace: u64,
+        pub ki_wchan: u64,
+        pub ki_pid: PidT,
+        pub ki_ppid: PidT,
+        pub ki_pgid: PidT,
+        pub ki_tpgid: PidT,
+        pub ki_sid: PidT,
+        pub ki_tsid: PidT,
+        pub ki_jobc: cty::c_short,
+        pub ki_spare_short1: cty::c_short,
+        pub ki_tdev: u32,
+        pub ki_tdev_freebsd11: u32,
+        pub ki_siglist: [u32; 4],
+        pub ki_sigmask: [u32; 4],
+        pub ki_sigignore: [u32; 4],
+        pub ki_sigcatch: [u32; 4],
+        pub ki_uid: UidT,
+        pub ki_ruid: UidT,
+        pub ki_svuid: UidT,
+        pub ki_rgid: GidT,
+        pub ki_svgid: GidT,
+        pub ki_ngroups: cty::c_short,
+        pub ki_spare_short2: cty::c_short,
+        pub ki_groups: [GidT; 16],
+        pub ki_size: u64,
+        pub ki_rssize: i64,
+        pub ki_swrss: i64,
+        pub ki_tsize: i64,
+        pub ki_dsize: i64,
+        pub ki_ssize: i64,
+        pub ki_xstat: u16,
+        pub ki_acflag: u16,
+        pub ki_pctcpu: u32,
+        pub ki_estcpu: u32,
+        pub ki_slptime: u32,
+        pub ki_swtime: u32,
+        pub ki_cow: u32,
+        pub ki_runtime: u64,
+        pub ki_start: super::Timeval,
+        pub ki_childtime: super::Timeval,
+        pub ki_flag: i64,
+        pub ki_kiflag: i64,
+        pub ki_traceflag: cty::c_int,
+        pub ki_stat: cty::c_char,
+        pub ki_nice: i8,
+        pub ki_lock: cty::c_char,
+        pub ki_rqindex: cty::c_char,
+        pub ki_oncpu: u8,
+        pub ki_lastcpu: u8,
+        pub ki_tdname: [cty::c_char; 17],
+        pub ki_wmesg: [cty::c_char; 9],
+        pub ki_login: [cty::c_char; 18],
+        pub ki_lockname: [cty::c_char; 9],
+        pub ki_comm: [cty::c_char; 20], // COMMLEN + 1
+        // Several further fields (jail name, emulation name, sparegen) follow but are not needed
+        // here; we size the read buffer from `ki_structsize` instead of this struct's own size, so
+        // trailing fields we don't model are simply skipped over per-entry.
+    }
+
+    pub fn pid(p: &KinfoProc) -> usize {
+        p.ki_pid as usize
+    }
+    pub fn ppid(p: &KinfoProc) -> usize {
+        p.ki_ppid as usize
+    }
+    pub fn pgrp(p: &KinfoProc) -> usize {
+        p.ki_pgid as usize
+    }
+    pub fn uid(p: &KinfoProc) -> usize {
+        p.ki_uid as usize
+    }
+    pub fn comm(p: &KinfoProc) -> String {
+        super::c_array_to_string(&p.ki_comm)
+    }
+}
+
+#[cfg(target_os = "macos")]
+use darwin::KinfoProc;
+#[cfg(target_os = "freebsd")]
+use freebsd::KinfoProc;
+
+#[repr(C)]
+struct Timeval {
+    tv_sec: i64,
+    tv_usec: i64,
+}
+
+fn c_array_to_string(raw: &[cty::c_char]) -> String {
+    let bytes: Vec<u8> = raw.iter().take_while(|&&c| c != 0).map(|&c| c as u8).collect();
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Fetch the raw `KERN_PROC_ALL` bytes via two `sysctl` calls (one to size the buffer, one to fill
+/// it), the standard dance for MIBs whose result size isn't known up front - see `sysctl(3)`.  The
+/// second call's `oldlenp` is always the buffer's actual byte capacity, so the kernel can never be
+/// told it has more room than it really does; `len` is updated in place to the true byte count
+/// actually written (which can be less than the size query reported, eg if processes exited
+/// between the two calls), and entry parsing below must use that true count, not the buffer's
+/// capacity.
+fn read_kinfo_bytes() -> Result<Vec<u8>, String> {
+    let mut mib = [CTL_KERN, KERN_PROC, KERN_PROC_ALL];
+    let mut len: SizeT = 0;
+    let rc = unsafe {
+        sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as cty::c_uint,
+            ptr::null_mut(),
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if rc != 0 {
+        return Err("sysctl(KERN_PROC_ALL) size query failed".to_string());
+    }
+
+    let mut buf: Vec<u8> = vec![0u8; len as usize];
+    let rc = unsafe {
+        sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as cty::c_uint,
+            buf.as_mut_ptr() as *mut std::ffi::c_void,
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if rc != 0 {
+        return Err("sysctl(KERN_PROC_ALL) data fetch failed".to_string());
+    }
+    buf.truncate(len as usize);
+    Ok(buf)
+}
+
+/// Darwin's `kinfo_proc` carries no self-describing size field (unlike FreeBSD's
+/// `ki_structsize`, see the FreeBSD `read_kinfo_procs` below), so the only stride available to us is
+/// this module's own compiled `size_of::<KinfoProc>()`.  If a future Darwin release changes the
+/// layout, this misparses silently rather than erroring - there's no field to notice the mismatch
+/// with - which is exactly the ABI instability the module-level doc comment above warns about.
+#[cfg(target_os = "macos")]
+fn read_kinfo_procs() -> Result<Vec<KinfoProc>, String> {
+    let raw = read_kinfo_bytes()?;
+    let stride = mem::size_of::<KinfoProc>();
+    let count = raw.len() / stride;
+    let mut procs = Vec::with_capacity(count);
+    for i in 0..count {
+        // Safe: `i * stride` stays within `raw`'s length for every `i < count` by construction,
+        // and `KinfoProc` has no padding-sensitive invariants beyond its field layout, which is
+        // what `read_unaligned` reproduces regardless of the source buffer's alignment.
+        let kp = unsafe { ptr::read_unaligned(raw.as_ptr().add(i * stride) as *const KinfoProc) };
+        procs.push(kp);
+    }
+    Ok(procs)
+}
+
+/// FreeBSD's `kinfo_proc` entries are self-describing: each one starts with a `ki_structsize: int`
+/// giving its own true size, which the kernel is free to grow (by appending fields) across
+/// releases without breaking older consumers.  We read that field directly and step by it, rather
+/// than by this module's compiled `size_of::<KinfoProc>()`, so a newer kernel with extra trailing
+/// fields we don't model still parses correctly instead of desyncing every subsequent entry.  An
+/// entry whose `ki_structsize` is smaller than the fields we read out of it would mean the kernel
+/// is older than what this module models; we skip such an entry (trusting `ki_structsize` for the
+/// stride regardless) rather than read past what it actually wrote.
+#[cfg(target_os = "freebsd")]
+fn read_kinfo_procs() -> Result<Vec<KinfoProc>, String> {
+    let raw = read_kinfo_bytes()?;
+    let min_size = mem::size_of::<KinfoProc>();
+    let mut procs = vec![];
+    let mut offset = 0usize;
+    while offset + mem::size_of::<cty::c_int>() <= raw.len() {
+        let structsize = unsafe {
+            ptr::read_unaligned(raw.as_ptr().add(offset) as *const cty::c_int)
+        } as usize;
+        if structsize == 0 || offset + structsize > raw.len() {
+            // Either a malformed/truncated trailing entry, or the kernel reported a stride that
+            // would run past what it actually gave us; either way there's nothing safe left to
+            // parse.
+            break;
+        }
+        if structsize >= min_size {
+            let kp = unsafe { ptr::read_unaligned(raw.as_ptr().add(offset) as *const KinfoProc) };
+            procs.push(kp);
+        }
+        offset += structsize;
+    }
+    Ok(procs)
+}
+
+/// Collect process information on macOS/FreeBSD from the `KERN_PROC_ALL` sysctl, producing the
+/// same `process::Process` records `process::get_process_information` returns on Linux.  `cpu_pct`
+/// and `cputime_sec` are left at zero here - plugging in the precise figures means going through
+/// `libproc`'s `proc_pidinfo`/`proc_pid_rusage` (Darwin) or `/proc`-less `kinfo_proc` fields that
+/// are well-defined on FreeBSD but not Darwin - which is future work tracked alongside this.
+pub fn get_process_information() -> Result<HashMap<usize, Process>, String> {
+    let procs = read_kinfo_procs()?;
+    let mut result = HashMap::new();
+    for p in &procs {
+        #[cfg(target_os = "macos")]
+        let (pid, ppid, pgrp, uid, comm) = (
+            darwin::pid(p),
+            darwin::ppid(p),
+            darwin::pgrp(p),
+            darwin::uid(p),
+            darwin::comm(p),
+        );
+        #[cfg(target_os = "freebsd")]
+        let (pid, ppid, pgrp, uid, comm) = (
+            freebsd::pid(p),
+            freebsd::ppid(p),
+            freebsd::pgrp(p),
+            freebsd::uid(p),
+            freebsd::comm(p),
+        );
+        if pid == 0 {
+            continue;
+        }
+        result.insert(
+            pid,
+            Process {
+                pid,
+                uid,
+                user: format!("_noinfo_{uid}"),
+                cpu_pct: 0.0,
+                mem_pct: 0.0,
+                cputime_sec: 0,
+                mem_size_kib: 0,
+                rss_kib: 0,
+                command: comm.clone(),
+                full_command: comm,
+                ppid,
+                session: pgrp,
+                state: '?',
+            },
+        );
+    }
+    Ok(result)
+}
@@ -0,0 +1,605 @@
+/// Collect whole-node health information: load average, per-filesystem disk/inode usage, CPU-state
+/// and task-state breakdowns, and memory/swap usage.
+///
+/// This is a sibling of `process`: per-process data tells us who's using the node, but not
+/// whether the node itself is overloaded or about to run out of disk or memory, which operators
+/// generally want to see alongside it.
+use crate::command::{self, CmdError};
+use crate::process;
+
+use std::collections::{HashMap, HashSet};
+
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    pub runnable: usize,
+    pub total: usize,
+}
+
+/// Read `/proc/loadavg` and return the 1/5/15-minute load averages together with the
+/// runnable/total task counts (the `runnable/total` field).
+pub fn get_load_average() -> Result<LoadAverage, String> {
+    let text = std::fs::read_to_string("/proc/loadavg")
+        .map_err(|e| format!("Could not read /proc/loadavg: {e}"))?;
+    parse_loadavg(&text)
+}
+
+fn parse_loadavg(text: &str) -> Result<LoadAverage, String> {
+    let fields = text.split_ascii_whitespace().collect::<Vec<&str>>();
+    if fields.len() < 4 {
+        return Err(format!("Unexpected /proc/loadavg: {text}"));
+    }
+    let one = fields[0]
+        .parse::<f64>()
+        .map_err(|_| format!("Bad 1-minute load average in /proc/loadavg: {text}"))?;
+    let five = fields[1]
+        .parse::<f64>()
+        .map_err(|_| format!("Bad 5-minute load average in /proc/loadavg: {text}"))?;
+    let fifteen = fields[2]
+        .parse::<f64>()
+        .map_err(|_| format!("Bad 15-minute load average in /proc/loadavg: {text}"))?;
+    let (runnable, total) = fields[3]
+        .split_once('/')
+        .ok_or_else(|| format!("Bad runnable/total field in /proc/loadavg: {text}"))?;
+    let runnable = runnable
+        .parse::<usize>()
+        .map_err(|_| format!("Bad runnable count in /proc/loadavg: {text}"))?;
+    let total = total
+        .parse::<usize>()
+        .map_err(|_| format!("Bad total task count in /proc/loadavg: {text}"))?;
+    Ok(LoadAverage {
+        one,
+        five,
+        fifteen,
+        runnable,
+        total,
+    })
+}
+
+/// Raw, monotonically-increasing per-state jiffy counters from the `cpu` summary line of
+/// `/proc/stat` (`cpu  user nice system idle iowait irq softirq steal ...`).  These are totals
+/// since boot and meaningless by themselves on a long-uptime host; `cpu_state_pct` below turns two
+/// samples taken an interval apart into a percentage breakdown.
+#[derive(Clone, Copy, Default)]
+pub struct CpuJiffies {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+}
+
+/// Read the `cpu` summary line of `/proc/stat`.
+pub fn get_cpu_jiffies() -> Result<CpuJiffies, String> {
+    let text = std::fs::read_to_string("/proc/stat")
+        .map_err(|e| format!("Could not read /proc/stat: {e}"))?;
+    parse_cpu_jiffies(&text)
+}
+
+fn parse_cpu_jiffies(text: &str) -> Result<CpuJiffies, String> {
+    let line = text
+        .lines()
+        .find(|l| l.starts_with("cpu "))
+        .ok_or_else(|| format!("No `cpu` summary line in /proc/stat: {text}"))?;
+    let fields = line.split_ascii_whitespace().skip(1).collect::<Vec<&str>>();
+    if fields.len() < 7 {
+        return Err(format!("Unexpected /proc/stat `cpu` line: {line}"));
+    }
+    let field = |i: usize| -> Result<u64, String> {
+        fields[i]
+            .parse::<u64>()
+            .map_err(|_| format!("Bad jiffy count in /proc/stat: {line}"))
+    };
+    Ok(CpuJiffies {
+        user: field(0)?,
+        nice: field(1)?,
+        system: field(2)?,
+        idle: field(3)?,
+        iowait: field(4)?,
+        irq: field(5)?,
+        softirq: field(6)?,
+        // `steal` was only added in Linux 2.6.11; treat it as absent (0) rather than fail on an
+        // ancient kernel, since it would otherwise show up as a constant 0% anyway.
+        steal: fields
+            .get(7)
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0),
+    })
+}
+
+/// The `%us/sy/ni/id/wa/hi/si/st` breakdown `top` reports, computed over the interval between two
+/// `CpuJiffies` samples.
+pub struct CpuStatePct {
+    pub user_pct: f64,
+    pub nice_pct: f64,
+    pub system_pct: f64,
+    pub idle_pct: f64,
+    pub iowait_pct: f64,
+    pub irq_pct: f64,
+    pub softirq_pct: f64,
+    pub steal_pct: f64,
+}
+
+/// Turn two `/proc/stat` samples - `previous` and `current`, taken some interval apart - into a
+/// CPU-state percentage breakdown of that interval.  `/proc/stat`'s counters are monotonic totals
+/// since boot, so a single sample can't say whether a 208-day-uptime host is idle or pegged; only
+/// the delta between two samples can.  Counters are expected to only increase, but we saturate the
+/// subtraction rather than panic in case `current` is actually older than `previous` (eg the
+/// caller's samples were taken out of order).
+pub fn cpu_state_pct(previous: &CpuJiffies, current: &CpuJiffies) -> CpuStatePct {
+    let d = |a: u64, b: u64| b.saturating_sub(a) as f64;
+    let user = d(previous.user, current.user);
+    let nice = d(previous.nice, current.nice);
+    let system = d(previous.system, current.system);
+    let idle = d(previous.idle, current.idle);
+    let iowait = d(previous.iowait, current.iowait);
+    let irq = d(previous.irq, current.irq);
+    let softirq = d(previous.softirq, current.softirq);
+    let steal = d(previous.steal, current.steal);
+    let total = user + nice + system + idle + iowait + irq + softirq + steal;
+    let pct = |x: f64| if total == 0.0 { 0.0 } else { 100.0 * x / total };
+    CpuStatePct {
+        user_pct: pct(user),
+        nice_pct: pct(nice),
+        system_pct: pct(system),
+        idle_pct: pct(idle),
+        iowait_pct: pct(iowait),
+        irq_pct: pct(irq),
+        softirq_pct: pct(softirq),
+        steal_pct: pct(steal),
+    }
+}
+
+/// Counts of processes in each broad run state, the way `top`'s "Tasks:" summary line breaks them
+/// down; derived from the same per-process `state` field `process`/`procfs` already capture
+/// (chunk1-1), rather than re-parsing `/proc` ourselves.
+#[derive(Default)]
+pub struct TaskStateCounts {
+    pub running: usize,
+    pub sleeping: usize,
+    pub uninterruptible_sleep: usize,
+    pub stopped: usize,
+    pub zombie: usize,
+    pub other: usize,
+}
+
+pub fn count_task_states(processes: &[process::Process]) -> TaskStateCounts {
+    let mut counts = TaskStateCounts::default();
+    for p in processes {
+        match p.state {
+            'R' => counts.running += 1,
+            'S' => counts.sleeping += 1,
+            'D' => counts.uninterruptible_sleep += 1,
+            'T' | 't' => counts.stopped += 1,
+            'Z' => counts.zombie += 1,
+            _ => counts.other += 1,
+        }
+    }
+    counts
+}
+
+/// Whole-node memory and swap usage from `/proc/meminfo`, in KiB as the file itself reports them.
+pub struct MemoryInfo {
+    pub total_kib: usize,
+    pub free_kib: usize,
+    pub available_kib: usize,
+    pub buffers_kib: usize,
+    pub cached_kib: usize,
+    pub swap_total_kib: usize,
+    pub swap_free_kib: usize,
+}
+
+/// Read `/proc/meminfo`.
+pub fn get_memory_info() -> Result<MemoryInfo, String> {
+    let text = std::fs::read_to_string("/proc/meminfo")
+        .map_err(|e| format!("Could not read /proc/meminfo: {e}"))?;
+    parse_meminfo(&text)
+}
+
+fn parse_meminfo(text: &str) -> Result<MemoryInfo, String> {
+    let fields = text
+        .lines()
+        .filter_map(|l| {
+            let (key, rest) = l.split_once(':')?;
+            let value = rest.trim().trim_end_matches(" kB").trim();
+            Some((key, value.parse::<usize>().ok()?))
+        })
+        .collect::<HashMap<&str, usize>>();
+    let field = |name: &str| -> Result<usize, String> {
+        fields
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("Missing {name} in /proc/meminfo"))
+    };
+    Ok(MemoryInfo {
+        total_kib: field("MemTotal")?,
+        free_kib: field("MemFree")?,
+        available_kib: field("MemAvailable")?,
+        buffers_kib: field("Buffers")?,
+        cached_kib: field("Cached")?,
+        swap_total_kib: field("SwapTotal")?,
+        swap_free_kib: field("SwapFree")?,
+    })
+}
+
+/// A single per-interval snapshot of whole-node health: load average, CPU-state breakdown,
+/// process run-state counts, and memory/swap usage.
+pub struct HostStats {
+    pub load_average: LoadAverage,
+    pub cpu_state_pct: CpuStatePct,
+    pub task_states: TaskStateCounts,
+    pub memory: MemoryInfo,
+}
+
+/// Sample whole-node health.  `previous_cpu_jiffies` must be the raw sample (`CpuJiffies`)
+/// returned alongside the previous call's `HostStats` - or, for the very first call of a run, a
+/// sample from `get_cpu_jiffies`.  `cpu_state_pct` reports 0% for every state only when no jiffies
+/// elapsed between the two samples; from `CpuJiffies::default()` it reports the average since boot.
+/// `processes` is the process list from this same sampling interval, so
+/// that `task_states` reflects the same point in time as the rest of the record.
+pub fn get_host_stats(
+    previous_cpu_jiffies: &CpuJiffies,
+    processes: &[process::Process],
+) -> Result<(HostStats, CpuJiffies), String> {
+    let load_average = get_load_average()?;
+    let current_cpu_jiffies = get_cpu_jiffies()?;
+    let stats = HostStats {
+        load_average,
+        cpu_state_pct: cpu_state_pct(previous_cpu_jiffies, &current_cpu_jiffies),
+        task_states: count_task_states(processes),
+        memory: get_memory_info()?,
+    };
+    Ok((stats, current_cpu_jiffies))
+}
+
+pub struct FilesystemUsage {
+    pub device: String,
+    pub mount_point: String,
+    pub total_kib: usize,
+    pub used_kib: usize,
+    pub avail_kib: usize,
+    pub total_inodes: usize,
+    pub used_inodes: usize,
+    pub avail_inodes: usize,
+    // Mirrors the `Capacity` / `IUse%` columns of `df -P` / `df -Pi`: the fraction of space or
+    // inodes in use, on a 0-100 scale, rounded the way `df` rounds (up, to the nearest percent).
+    // `used_kib`/`used_inodes` alone don't tell an operator how close a volume is to full without
+    // also doing this division themselves, so we do it once here.
+    pub capacity_pct: f64,
+    pub inode_capacity_pct: f64,
+}
+
+// `df`'s `Capacity` column is `ceil(100 * used / (used + avail))`, not `used / total`, since on
+// ext-family filesystems `avail` excludes the root-reserved blocks that still count towards
+// `total`; matching it keeps our number in agreement with what operators already expect.
+fn capacity_pct(used: usize, avail: usize) -> f64 {
+    let denom = used + avail;
+    if denom == 0 {
+        0.0
+    } else {
+        (100.0 * used as f64 / denom as f64).ceil()
+    }
+}
+
+// The pseudo-filesystems that show up in /proc/mounts alongside real disks, all backed by memory
+// or the kernel rather than actual storage; operators care about `/dev/nvme0n1p3`, not `tmpfs`.
+const PSEUDO_FSTYPES: &[&str] = &[
+    "proc",
+    "sysfs",
+    "devtmpfs",
+    "devpts",
+    "tmpfs",
+    "cgroup",
+    "cgroup2",
+    "securityfs",
+    "pstore",
+    "bpf",
+    "tracefs",
+    "debugfs",
+    "mqueue",
+    "hugetlbfs",
+    "autofs",
+    "rpc_pipefs",
+    "nsfs",
+    "binfmt_misc",
+    "configfs",
+    "fusectl",
+    "overlay",
+    "squashfs",
+];
+
+const TIMEOUT_SECONDS: u64 = 2; // for `df`, as for `ps` in process.rs
+
+const DF_COMMAND: &str =
+    "df --output=source,target,itotal,iused,iavail,blocks,used,avail --block-size=1024 --no-sync";
+
+/// Obtain disk/inode usage for every real, mounted filesystem.
+///
+/// This will attempt to get the values from `/proc/mounts` and `statvfs` first, and if that
+/// fails, it will run `df`.
+pub fn get_filesystem_usage() -> Result<Vec<FilesystemUsage>, CmdError> {
+    if let Some(result) = get_filesystem_usage_native() {
+        Ok(result)
+    } else {
+        match command::safe_command(DF_COMMAND, TIMEOUT_SECONDS) {
+            Ok(out) => Ok(parse_df_output(&out)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn get_filesystem_usage_native() -> Option<Vec<FilesystemUsage>> {
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+    Some(
+        real_mounts(&mounts)
+            .into_iter()
+            .filter_map(|(device, mount_point)| statvfs_usage(&device, &mount_point))
+            .collect(),
+    )
+}
+
+/// Parse `/proc/mounts` (`device mount_point fstype options dump pass`) and return the
+/// `(device, mount_point)` pairs for every mount whose fstype is not one of `PSEUDO_FSTYPES` and
+/// whose device is a path (starts with '/'), so eg an NFS `host:/export` mount is left out.
+fn real_mounts(text: &str) -> Vec<(String, String)> {
+    let pseudo: HashSet<&str> = PSEUDO_FSTYPES.iter().copied().collect();
+    text.lines()
+        .filter_map(|l| {
+            let fields = l.split_ascii_whitespace().collect::<Vec<&str>>();
+            if fields.len() < 3 {
+                return None;
+            }
+            let (device, mount_point, fstype) = (fields[0], fields[1], fields[2]);
+            if pseudo.contains(fstype) || !device.starts_with('/') {
+                return None;
+            }
+            Some((device.to_string(), mount_point.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn statvfs_usage(device: &str, mount_point: &str) -> Option<FilesystemUsage> {
+    let path = std::ffi::CString::new(mount_point).ok()?;
+    let mut buf: libc_statvfs::Statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc_statvfs::statvfs(path.as_ptr(), &mut buf) };
+    if rc != 0 {
+        return None;
+    }
+    let block_kib = buf.f_frsize as usize / 1024;
+    let total_kib = buf.f_blocks as usize * block_kib;
+    let avail_kib = buf.f_bavail as usize * block_kib;
+    let used_kib = total_kib.saturating_sub(buf.f_bfree as usize * block_kib);
+    let used_inodes = (buf.f_files - buf.f_ffree) as usize;
+    let avail_inodes = buf.f_favail as usize;
+    Some(FilesystemUsage {
+        device: device.to_string(),
+        mount_point: mount_point.to_string(),
+        total_kib,
+        used_kib,
+        avail_kib,
+        total_inodes: buf.f_files as usize,
+        used_inodes,
+        avail_inodes,
+        capacity_pct: capacity_pct(used_kib, avail_kib),
+        inode_capacity_pct: capacity_pct(used_inodes, avail_inodes),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn statvfs_usage(_device: &str, _mount_point: &str) -> Option<FilesystemUsage> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod libc_statvfs {
+    // Mirrors glibc's `struct statvfs` on 64-bit Linux (see `man 3 statvfs`); we only need a
+    // handful of the fields it exposes.
+    #[repr(C)]
+    pub struct Statvfs {
+        pub f_bsize: cty::c_ulong,
+        pub f_frsize: cty::c_ulong,
+        pub f_blocks: cty::uint64_t,
+        pub f_bfree: cty::uint64_t,
+        pub f_bavail: cty::uint64_t,
+        pub f_files: cty::uint64_t,
+        pub f_ffree: cty::uint64_t,
+        pub f_favail: cty::uint64_t,
+        pub f_fsid: cty::c_ulong,
+        pub f_flag: cty::c_ulong,
+        pub f_namemax: cty::c_ulong,
+        pub __f_spare: [cty::c_int; 6],
+    }
+
+    extern "C" {
+        pub fn statvfs(path: *const cty::c_char, buf: *mut Statvfs) -> cty::c_int;
+    }
+}
+
+fn parse_df_output(raw_text: &str) -> Vec<FilesystemUsage> {
+    raw_text
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_ascii_whitespace().collect::<Vec<&str>>();
+            if fields.len() < 8 {
+                return None;
+            }
+            let (used_inodes, avail_inodes) = (
+                fields[3].parse::<usize>().ok()?,
+                fields[4].parse::<usize>().ok()?,
+            );
+            let (used_kib, avail_kib) = (
+                fields[6].parse::<usize>().ok()?,
+                fields[7].parse::<usize>().ok()?,
+            );
+            Some(FilesystemUsage {
+                device: fields[0].to_string(),
+                mount_point: fields[1].to_string(),
+                total_inodes: fields[2].parse::<usize>().ok()?,
+                used_inodes,
+                avail_inodes,
+                total_kib: fields[5].parse::<usize>().ok()?,
+                used_kib,
+                avail_kib,
+                capacity_pct: capacity_pct(used_kib, avail_kib),
+                inode_capacity_pct: capacity_pct(used_inodes, avail_inodes),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_parse_loadavg() {
+    let l = parse_loadavg("0.52 0.58 0.59 3/512 12345\n").expect("Test: must parse");
+    assert_eq!(l.one, 0.52);
+    assert_eq!(l.five, 0.58);
+    assert_eq!(l.fifteen, 0.59);
+    assert_eq!(l.runnable, 3);
+    assert_eq!(l.total, 512);
+}
+
+#[test]
+fn test_parse_loadavg_rejects_garbage() {
+    assert!(parse_loadavg("not a loadavg line").is_err());
+}
+
+#[test]
+fn test_real_mounts_filters_pseudo_filesystems() {
+    let text = "\
+/dev/nvme0n1p3 / ext4 rw,relatime 0 0
+tmpfs /run tmpfs rw,nosuid,nodev 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+cgroup2 /sys/fs/cgroup cgroup2 rw,nosuid,nodev,noexec,relatime 0 0
+/dev/sda1 /home ext4 rw,relatime 0 0
+";
+    let mounts = real_mounts(text);
+    assert_eq!(
+        mounts,
+        vec![
+            ("/dev/nvme0n1p3".to_string(), "/".to_string()),
+            ("/dev/sda1".to_string(), "/home".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_df_output() {
+    let text = "\
+/dev/nvme0n1p3 / 1000000 400000 600000 103212568 41453768 56545824
+/dev/sda1 /home 2000000 100000 1900000 499748404 123456 494630400
+";
+    let usage = parse_df_output(text);
+    assert_eq!(usage.len(), 2);
+    assert_eq!(usage[0].device, "/dev/nvme0n1p3");
+    assert_eq!(usage[0].mount_point, "/");
+    assert_eq!(usage[0].total_inodes, 1000000);
+    assert_eq!(usage[0].used_inodes, 400000);
+    assert_eq!(usage[0].avail_inodes, 600000);
+    assert_eq!(usage[0].total_kib, 103212568);
+    assert_eq!(usage[0].used_kib, 41453768);
+    assert_eq!(usage[0].avail_kib, 56545824);
+}
+
+#[test]
+fn test_capacity_pct() {
+    // 94% full, rounded up, matching `df`'s own rounding rather than truncating.
+    assert_eq!(capacity_pct(940, 61), 94.0);
+    assert_eq!(capacity_pct(0, 1000), 0.0);
+    assert_eq!(capacity_pct(0, 0), 0.0);
+}
+
+#[test]
+fn test_parse_cpu_jiffies() {
+    let text = "\
+cpu  12345 234 5678 987654 1011 0 222 99
+cpu0 6000 100 2800 493827 500 0 111 50
+intr 12345 0 0
+";
+    let j = parse_cpu_jiffies(text).expect("Test: must parse");
+    assert_eq!(j.user, 12345);
+    assert_eq!(j.nice, 234);
+    assert_eq!(j.system, 5678);
+    assert_eq!(j.idle, 987654);
+    assert_eq!(j.iowait, 1011);
+    assert_eq!(j.irq, 0);
+    assert_eq!(j.softirq, 222);
+    assert_eq!(j.steal, 99);
+}
+
+#[test]
+fn test_parse_cpu_jiffies_rejects_garbage() {
+    assert!(parse_cpu_jiffies("intr 12345 0 0\n").is_err());
+}
+
+#[test]
+fn test_cpu_state_pct_is_a_delta_not_a_raw_total() {
+    // On a long-uptime box `idle` alone dwarfs everything else; only the delta between two
+    // samples says anything about what happened during the interval.
+    let previous = CpuJiffies {
+        user: 1_000_000,
+        idle: 50_000_000,
+        ..Default::default()
+    };
+    let current = CpuJiffies {
+        user: 1_000_100,
+        idle: 50_000_100,
+        ..Default::default()
+    };
+    let pct = cpu_state_pct(&previous, &current);
+    assert_eq!(pct.user_pct, 50.0);
+    assert_eq!(pct.idle_pct, 50.0);
+    assert_eq!(pct.system_pct, 0.0);
+}
+
+#[test]
+fn test_cpu_state_pct_zero_interval() {
+    let sample = CpuJiffies {
+        idle: 50_000_000,
+        ..Default::default()
+    };
+    let pct = cpu_state_pct(&sample, &sample);
+    assert_eq!(pct.idle_pct, 0.0);
+}
+
+#[test]
+fn test_count_task_states() {
+    let processes = process::parsed_test_output();
+    let counts = count_task_states(&processes);
+    assert_eq!(counts.running, 1); // chromium, R
+    assert_eq!(counts.sleeping, 4); // slack, chromium, slack, some app
+    assert_eq!(counts.uninterruptible_sleep, 1); // someapp, D
+    assert_eq!(counts.zombie, 1); // some app, Z
+    assert_eq!(counts.stopped, 0);
+    assert_eq!(counts.other, 0);
+}
+
+#[test]
+fn test_parse_meminfo() {
+    let text = "\
+MemTotal:       16384000 kB
+MemFree:         1024000 kB
+MemAvailable:    8192000 kB
+Buffers:          256000 kB
+Cached:          4096000 kB
+SwapTotal:       2048000 kB
+SwapFree:        2048000 kB
+Shmem:             12345 kB
+";
+    let mem = parse_meminfo(text).expect("Test: must parse");
+    assert_eq!(mem.total_kib, 16384000);
+    assert_eq!(mem.free_kib, 1024000);
+    assert_eq!(mem.available_kib, 8192000);
+    assert_eq!(mem.buffers_kib, 256000);
+    assert_eq!(mem.cached_kib, 4096000);
+    assert_eq!(mem.swap_total_kib, 2048000);
+    assert_eq!(mem.swap_free_kib, 2048000);
+}
+
+#[test]
+fn test_parse_meminfo_rejects_missing_field() {
+    assert!(parse_meminfo("MemTotal: 16384000 kB\n").is_err());
+}
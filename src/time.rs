@@ -54,10 +54,29 @@ pub fn now_local() -> libc::tm {
 }
 
 // Parse a timestamp into components.  I guess we could use libc::strptime here but for now let's
-// just handle yyyy-mm-ddThh:mm[:ss] and leave the localtime fields blank.  Here we must return a Result
-// b/c this may depend on user input.
+// just handle yyyy-mm-ddThh:mm[:ss] with an optional trailing RFC 3339 zone offset (`Z`, `+hh:mm`,
+// `-hh:mm`, or `+hhmm`/`-hhmm`) and leave the remaining localtime fields blank.  Here we must
+// return a Result b/c this may depend on user input.
+//
+// `parse_date_and_time` accepts the offset and records it (in seconds east of UTC) in tm_gmtoff,
+// so that it can round-trip whatever format_iso8601 produced.  `parse_date_and_time_no_tzo` is the
+// original, offset-less entry point; it now just rejects any input that carries an offset, so
+// existing callers that assume local/unzoned input are unaffected.
+
+pub fn parse_date_and_time(s: &str) -> Result<libc::tm, String> {
+    let (tm, _had_offset) = parse_date_and_time_impl(s)?;
+    Ok(tm)
+}
 
 pub fn parse_date_and_time_no_tzo(s: &str) -> Result<libc::tm, String> {
+    let (tm, had_offset) = parse_date_and_time_impl(s)?;
+    if had_offset {
+        return Err("Unexpected timezone offset".to_string());
+    }
+    Ok(tm)
+}
+
+fn parse_date_and_time_impl(s: &str) -> Result<(libc::tm, bool), String> {
     let components = s.split('T').collect::<Vec<&str>>();
     if components.len() != 2 {
         return Err("Expected ...T...".to_string());
@@ -66,7 +85,8 @@ pub fn parse_date_and_time_no_tzo(s: &str) -> Result<libc::tm, String> {
     if ymd.len() != 3 {
         return Err("Expected yyyy-mm-dd".to_string());
     }
-    let hms = components[1].split(':').collect::<Vec<&str>>();
+    let (time_part, gmtoff, had_offset) = split_off_zone_offset(components[1])?;
+    let hms = time_part.split(':').collect::<Vec<&str>>();
     if hms.len() != 2 && hms.len() != 3 {
         return Err("Expected hh:mm".to_string());
     }
@@ -94,23 +114,65 @@ pub fn parse_date_and_time_no_tzo(s: &str) -> Result<libc::tm, String> {
     {
         return Err("Date field out of range".to_string());
     }
-    Ok(libc::tm {
-        tm_sec: ss as i32,
-        tm_min: mi as i32,
-        tm_hour: hr as i32,
-        tm_mday: dy as i32,
-        tm_mon: (mo - 1) as i32,
-        tm_year: (yr - 1900) as i32,
-        tm_wday: 0,
-        tm_yday: 0,
-        tm_isdst: 0,
-        tm_gmtoff: 0,
-        tm_zone: std::ptr::null(),
-    })
+    Ok((
+        libc::tm {
+            tm_sec: ss as i32,
+            tm_min: mi as i32,
+            tm_hour: hr as i32,
+            tm_mday: dy as i32,
+            tm_mon: (mo - 1) as i32,
+            tm_year: (yr - 1900) as i32,
+            tm_wday: 0,
+            tm_yday: 0,
+            tm_isdst: 0,
+            tm_gmtoff: gmtoff,
+            tm_zone: std::ptr::null(),
+        },
+        had_offset,
+    ))
+}
+
+// Split the time-of-day component (everything after the 'T') into the bare hh:mm[:ss] part and an
+// optional zone offset, returning the offset in seconds east of UTC and whether one was present at
+// all.  The sign character of a `+hh:mm`/`-hh:mm`/`+hhmm`/`-hhmm` offset cannot be confused with
+// the '-' separating the yyyy-mm-dd fields because those have already been split off by the caller
+// (on 'T'), so any '+'/'-' remaining here can only be the offset sign.
+
+fn split_off_zone_offset(s: &str) -> Result<(&str, libc::time_t, bool), String> {
+    if let Some(rest) = s.strip_suffix('Z') {
+        return Ok((rest, 0, true));
+    }
+    if let Some(ix) = s.rfind(['+', '-']) {
+        let negative = &s[ix..ix + 1] == "-";
+        let (oh, om) = parse_offset_hhmm(&s[ix + 1..])?;
+        if oh > 23 || om > 59 {
+            return Err("Offset field out of range".to_string());
+        }
+        let gmtoff = (oh as libc::time_t * 3600 + om as libc::time_t * 60)
+            * if negative { -1 } else { 1 };
+        return Ok((&s[..ix], gmtoff, true));
+    }
+    Ok((s, 0, false))
+}
+
+fn parse_offset_hhmm(s: &str) -> Result<(u32, u32), String> {
+    if let Some((h, m)) = s.split_once(':') {
+        Ok((
+            h.parse::<u32>().map_err(parse_int_err)?,
+            m.parse::<u32>().map_err(parse_int_err)?,
+        ))
+    } else if s.len() == 4 && s.bytes().all(|b| b.is_ascii_digit()) {
+        Ok((
+            s[0..2].parse::<u32>().map_err(parse_int_err)?,
+            s[2..4].parse::<u32>().map_err(parse_int_err)?,
+        ))
+    } else {
+        Err("Expected an hh:mm or hhmm offset".to_string())
+    }
 }
 
 fn parse_int_err(_e: ParseIntError) -> String {
-    return "Not an unsigned int value".to_string();
+    "Not an unsigned int value".to_string()
 }
 
 // Format a time as an ISO time stamp: yyyy-mm-ddThh:mm:ss+hh:mm
@@ -196,3 +258,34 @@ pub fn test_parse_date_and_time_no_tzo() {
     assert!(parse_date_and_time_no_tzo("2022-07-01T2359").is_err());
     assert!(parse_date_and_time_no_tzo("2022-07-01T23:59+03:30").is_err());
 }
+
+#[test]
+pub fn test_parse_date_and_time() {
+    let t = parse_date_and_time("2022-07-01T23:59:14Z").unwrap();
+    assert!(t.tm_year == 2022-1900 && t.tm_mon == 7-1 && t.tm_mday == 1);
+    assert!(t.tm_hour == 23 && t.tm_min == 59 && t.tm_sec == 14 && t.tm_gmtoff == 0);
+
+    let t = parse_date_and_time("2022-07-01T23:59+03:30").unwrap();
+    assert!(t.tm_hour == 23 && t.tm_min == 59 && t.tm_gmtoff == 3*3600 + 30*60);
+
+    let t = parse_date_and_time("2022-07-01T23:59-03:30").unwrap();
+    assert!(t.tm_gmtoff == -(3*3600 + 30*60));
+
+    let t = parse_date_and_time("2022-07-01T23:59+0330").unwrap();
+    assert!(t.tm_gmtoff == 3*3600 + 30*60);
+
+    let t = parse_date_and_time("2022-07-01T23:59-0330").unwrap();
+    assert!(t.tm_gmtoff == -(3*3600 + 30*60));
+
+    // No offset is still accepted, with tm_gmtoff left at 0.
+    let t = parse_date_and_time("2022-07-01T23:59:14").unwrap();
+    assert!(t.tm_gmtoff == 0);
+
+    assert!(parse_date_and_time("2022-07-01T23:59+24:00").is_err());
+    assert!(parse_date_and_time("2022-07-01T23:59+00:60").is_err());
+    assert!(parse_date_and_time("2022-07-01T23:59+330").is_err());
+
+    // parse_date_and_time_no_tzo must still reject anything carrying an offset.
+    assert!(parse_date_and_time_no_tzo("2022-07-01T23:59:14Z").is_err());
+    assert!(parse_date_and_time_no_tzo("2022-07-01T23:59+0330").is_err());
+}